@@ -0,0 +1,259 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+#[ink::contract]
+mod counter_consumer {
+    use simple_counter::{Counter, CounterError, CounterSubscriber};
+
+    /// Milestone reported by the counter: `(milestone, value, rising)`.
+    pub type MilestoneReport = (u128, u128, bool);
+
+    /// Example contract that changes a `SimpleCounter` through the `Counter` trait.
+    /// Its own account has to be whitelisted in the counter (or hold the
+    /// incrementer role) for `bump` to succeed. It can also be registered as the
+    /// counter's subscriber to keep track of the last milestone reached.
+    #[ink(storage)]
+    pub struct CounterConsumer {
+        counter: ink::contract_ref!(Counter),
+        last_milestone: Option<MilestoneReport>,
+    }
+
+    impl CounterSubscriber for CounterConsumer {
+        /// Only reports coming from the counter are recorded.
+        #[ink(message)]
+        fn on_milestone(&mut self, milestone: u128, value: u128, rising: bool) {
+            if self.env().caller() == self.counter() {
+                self.last_milestone = Some((milestone, value, rising));
+            }
+        }
+    }
+
+    impl CounterConsumer {
+        #[ink(constructor)]
+        pub fn new(counter: AccountId) -> Self {
+            Self { counter: counter.into(), last_milestone: None }
+        }
+
+        /// Increases the counter by one, forwarding its error if it fails.
+        #[ink(message)]
+        pub fn bump(&mut self) -> Result<(), CounterError> {
+            self.counter.increase_number()
+        }
+
+        #[ink(message)]
+        pub fn current(&self) -> u128 {
+            self.counter.retrieve_number()
+        }
+
+        #[ink(message)]
+        pub fn counter(&self) -> AccountId {
+            *self.counter.as_ref()
+        }
+
+        #[ink(message)]
+        pub fn last_milestone(&self) -> Option<MilestoneReport> {
+            self.last_milestone
+        }
+    }
+
+    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
+    /// module and test functions are marked with a `#[test]` attribute.
+    /// The below code is technically just normal Rust code.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+
+        use ink::codegen::TraitCallBuilder;
+        use ink::scale::Encode;
+
+        #[ink::test]
+        fn calls_target_the_counter_with_its_selectors() {
+            // El entorno off-chain no soporta llamadas entre contratos, así que armamos
+            // las llamadas sin ejecutarlas y revisamos destino y selector.
+            let counter = AccountId::from([0x07; 32]);
+            let mut consumer = CounterConsumer::new(counter);
+            assert_eq!(consumer.counter(), counter);
+
+            let bump = TraitCallBuilder::call_mut(&mut consumer.counter).increase_number().params();
+            assert_eq!(*bump.callee(), counter);
+            assert_eq!(bump.exec_input().encode()[..4], ink::selector_bytes!("increase_number"));
+
+            let current = TraitCallBuilder::call(&consumer.counter).retrieve_number().params();
+            assert_eq!(*current.callee(), counter);
+            assert_eq!(current.exec_input().encode()[..4], ink::selector_bytes!("retrieve_number"));
+        }
+
+        #[ink::test]
+        fn only_the_counter_can_report_milestones() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let counter = AccountId::from([0x07; 32]);
+            let mut consumer = CounterConsumer::new(counter);
+            assert_eq!(consumer.last_milestone(), None);
+
+            // Un reporte de otra cuenta se ignora.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            consumer.on_milestone(10, 12, true);
+            assert_eq!(consumer.last_milestone(), None);
+
+            // El contador sí puede reportar, y el último reporte reemplaza al anterior.
+            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(counter);
+            consumer.on_milestone(10, 12, true);
+            assert_eq!(consumer.last_milestone(), Some((10, 12, true)));
+            consumer.on_milestone(10, 9, false);
+            assert_eq!(consumer.last_milestone(), Some((10, 9, false)));
+        }
+    }
+
+    /// End-to-end tests: need a running node and `simple_counter` as a dependency.
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::ContractsBackend;
+        use simple_counter::{SimpleCounter, SimpleCounterRef};
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test]
+        async fn bump_increases_the_counter<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            // Desplegamos el contador con `alice` como owner.
+            let mut counter_constructor = SimpleCounterRef::new();
+            let counter = client
+                .instantiate("simple_counter", &ink_e2e::alice(), &mut counter_constructor)
+                .submit()
+                .await
+                .expect("simple_counter instantiate failed");
+            let mut counter_call = counter.call_builder::<SimpleCounter>();
+
+            // Desplegamos el consumidor apuntando al contador.
+            let mut consumer_constructor = CounterConsumerRef::new(counter.account_id);
+            let consumer = client
+                .instantiate("counter_consumer", &ink_e2e::alice(), &mut consumer_constructor)
+                .submit()
+                .await
+                .expect("counter_consumer instantiate failed");
+            let mut consumer_call = consumer.call_builder::<CounterConsumer>();
+
+            // Sin estar en la whitelist, el contador rechaza al consumidor.
+            let bump = consumer_call.bump();
+            let result = client.call(&ink_e2e::bob(), &bump).dry_run().await?;
+            assert_eq!(result.return_value(), Err(CounterError::NotWhitelisted));
+
+            // `alice` agrega al consumidor a la whitelist y `bob` incrementa a través de él.
+            let add = counter_call.add_to_white_list(consumer.account_id, None);
+            client
+                .call(&ink_e2e::alice(), &add)
+                .submit()
+                .await
+                .expect("add_to_white_list failed");
+            client
+                .call(&ink_e2e::bob(), &bump)
+                .submit()
+                .await
+                .expect("bump failed");
+
+            let current = consumer_call.current();
+            let result = client.call(&ink_e2e::bob(), &current).dry_run().await?;
+            assert_eq!(result.return_value(), 1);
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn subscriber_is_notified_of_milestones<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            // Desplegamos el contador y el consumidor; `alice` se agrega a la whitelist.
+            let mut counter_constructor = SimpleCounterRef::new();
+            let counter = client
+                .instantiate("simple_counter", &ink_e2e::alice(), &mut counter_constructor)
+                .submit()
+                .await
+                .expect("simple_counter instantiate failed");
+            let mut counter_call = counter.call_builder::<SimpleCounter>();
+            let mut consumer_constructor = CounterConsumerRef::new(counter.account_id);
+            let consumer = client
+                .instantiate("counter_consumer", &ink_e2e::alice(), &mut consumer_constructor)
+                .submit()
+                .await
+                .expect("counter_consumer instantiate failed");
+            let consumer_call = consumer.call_builder::<CounterConsumer>();
+            let add = counter_call.add_to_white_list(ink_e2e::account_id(ink_e2e::AccountKeyring::Alice), None);
+            client
+                .call(&ink_e2e::alice(), &add)
+                .submit()
+                .await
+                .expect("add_to_white_list failed");
+
+            // `alice` registra el hito 1 y al consumidor como suscriptor.
+            let milestone = counter_call.add_milestone(1);
+            client
+                .call(&ink_e2e::alice(), &milestone)
+                .submit()
+                .await
+                .expect("add_milestone failed");
+            let subscribe = counter_call.set_subscriber(Some(consumer.account_id));
+            client
+                .call(&ink_e2e::alice(), &subscribe)
+                .submit()
+                .await
+                .expect("set_subscriber failed");
+
+            // Al cruzar el hito, el contador llama a `on_milestone` del consumidor. El cambio
+            // se hace directo en el contador: pasar por `bump` sería una reentrada al consumidor.
+            let increase = counter_call.increase_number();
+            client
+                .call(&ink_e2e::alice(), &increase)
+                .submit()
+                .await
+                .expect("increase_number failed");
+            let last = consumer_call.last_milestone();
+            let result = client.call(&ink_e2e::bob(), &last).dry_run().await?;
+            assert_eq!(result.return_value(), Some((1, 1, true)));
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn failing_subscriber_does_not_block_changes<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            // Desplegamos el contador con `alice` como owner y en la whitelist.
+            let mut counter_constructor = SimpleCounterRef::new();
+            let counter = client
+                .instantiate("simple_counter", &ink_e2e::alice(), &mut counter_constructor)
+                .submit()
+                .await
+                .expect("simple_counter instantiate failed");
+            let mut counter_call = counter.call_builder::<SimpleCounter>();
+            let add = counter_call.add_to_white_list(ink_e2e::account_id(ink_e2e::AccountKeyring::Alice), None);
+            client
+                .call(&ink_e2e::alice(), &add)
+                .submit()
+                .await
+                .expect("add_to_white_list failed");
+
+            // El suscriptor es una cuenta sin contrato, así que la llamada siempre falla.
+            let milestone = counter_call.add_milestone(1);
+            client
+                .call(&ink_e2e::alice(), &milestone)
+                .submit()
+                .await
+                .expect("add_milestone failed");
+            let subscribe = counter_call.set_subscriber(Some(ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie)));
+            client
+                .call(&ink_e2e::alice(), &subscribe)
+                .submit()
+                .await
+                .expect("set_subscriber failed");
+
+            // Aun así el incremento que cruza el hito se aplica.
+            let increase = counter_call.increase_number();
+            client
+                .call(&ink_e2e::alice(), &increase)
+                .submit()
+                .await
+                .expect("increase_number failed");
+            let retrieve = counter_call.retrieve_number();
+            let result = client.call(&ink_e2e::alice(), &retrieve).dry_run().await?;
+            assert_eq!(result.return_value(), 1);
+
+            Ok(())
+        }
+    }
+}
@@ -1,9 +1,177 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
+pub use self::simple_counter::{SimpleCounter, SimpleCounterRef};
+
+use ink::scale::{Encode, Decode};
+use scale_info::TypeInfo;
+
+/// Errors returned by the counter messages, so clients can decode the
+/// failure reason instead of getting an opaque revert.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CounterError {
+    /// The caller is not the owner of the contract.
+    NotOwner,
+    /// The caller is not in the white list.
+    NotWhitelisted,
+    /// The operation would overflow the counter.
+    Overflow,
+    /// The operation would underflow the counter.
+    Underflow,
+    /// There is no ownership transfer in progress.
+    NoPendingOwner,
+    /// The caller is not the pending owner.
+    NotPendingOwner,
+    /// The caller does not have the role required by the message.
+    MissingRole,
+    /// The new value would fall outside `min_value..=max_value`.
+    OutOfBounds,
+    /// `min_value` is greater than `max_value`, or the current value is outside them.
+    InvalidBounds,
+    /// The batch has more than `MAX_BATCH_SIZE` accounts.
+    BatchTooLarge,
+    /// The caller's white list entry has expired.
+    AccessExpired,
+    /// The expiry is not in the future.
+    InvalidExpiry,
+    /// The caller has to wait until `next_allowed_call` before changing the counter again.
+    RateLimited,
+    /// The quota allows zero calls or has an empty window.
+    InvalidRateLimit,
+    /// There is no counter with the given id.
+    UnknownCounter,
+    /// The caller is not the admin of the counter.
+    NotCounterAdmin,
+    /// The contract is paused.
+    Paused,
+    /// The contract is not paused.
+    NotPaused,
+    /// The storage is already at `STORAGE_VERSION`.
+    AlreadyMigrated,
+    /// `set_code_hash` failed, e.g. because the code hash is not uploaded.
+    SetCodeFailed,
+    /// The deadline of the signed authorization has passed.
+    SignatureExpired,
+    /// The nonce is not the next one expected from the signer.
+    InvalidNonce,
+    /// The signature was not produced by the signer over the expected payload.
+    InvalidSignature,
+    /// No Merkle root has been published.
+    NoMerkleRoot,
+    /// The Merkle proof does not lead to the published root.
+    InvalidProof,
+    /// There are already `MAX_MILESTONES` milestones.
+    TooManyMilestones,
+    /// The milestone is already registered.
+    MilestoneExists,
+    /// The milestone is not registered.
+    UnknownMilestone,
+    /// The transferred value does not cover the fee of the call.
+    FeeTooLow,
+    /// The public fee has to be higher than the regular fee.
+    InvalidFees,
+    /// The amount exceeds the fees collected so far.
+    InsufficientFees,
+    /// The transfer of collected fees failed.
+    TransferFailed,
+    /// There is no reset proposal with the given id.
+    NoResetProposal,
+    /// The deadline of the reset proposal has passed.
+    ResetProposalExpired,
+    /// The caller already approved the reset proposal.
+    AlreadyApproved,
+    /// The proposer of a reset cannot approve it.
+    ProposerCannotApprove,
+    /// The reset proposal does not have enough approvals yet.
+    NotEnoughApprovals,
+    /// The approval threshold and validity period have to be greater than zero.
+    InvalidResetPolicy,
+}
+
+/// Messages of the counter that other contracts can call through
+/// `ink::contract_ref!(Counter)` without hand-building selectors.
+/// The selectors are pinned to the ones these messages had before the trait
+/// existed, so deployed clients and upgraded instances keep working.
+#[ink::trait_definition]
+pub trait Counter {
+    #[ink(message, payable, selector = 0x792876de)]
+    fn increase_number(&mut self) -> Result<(), CounterError>;
+
+    #[ink(message, payable, selector = 0xd72839cb)]
+    fn decrease_number(&mut self) -> Result<(), CounterError>;
+
+    #[ink(message, selector = 0x1c85fbfd)]
+    fn retrieve_number(&self) -> u128;
+}
+
+/// Callback implemented by contracts that want to be notified when the counter
+/// crosses one of its milestones.
+#[ink::trait_definition]
+pub trait CounterSubscriber {
+    /// `rising` is `true` when the counter went from below `milestone` to at least `milestone`.
+    #[ink(message)]
+    fn on_milestone(&mut self, milestone: u128, value: u128, rising: bool);
+}
+
 #[ink::contract]
 mod simple_counter {
-    use ink::storage::Mapping;
+    use super::{Counter, CounterError, CounterSubscriber};
+    use ink::codegen::TraitCallBuilder;
+    use ink::storage::{Lazy, Mapping};
+    use ink::prelude::vec::Vec;
+    use ink::prelude::string::String;
+    use ink::env::hash::Blake2x256;
+    use ink::scale::{Encode, Decode};
+    use scale_info::TypeInfo;
+
+    /// Identifier of a role in the access control registry.
+    pub type RoleId = u32;
+
+    /// Can manage the white list and, by default, grant and revoke every role.
+    pub const ADMIN_ROLE: RoleId = 0;
+    /// Can call `increase_number`.
+    pub const INCREMENTER_ROLE: RoleId = 1;
+    /// Can call `decrease_number`.
+    pub const DECREMENTER_ROLE: RoleId = 2;
+    /// Can call `pause` and `unpause`.
+    pub const PAUSER_ROLE: RoleId = 3;
+    /// Can approve reset proposals.
+    pub const RESET_APPROVER_ROLE: RoleId = 4;
+
+    /// Storage layout version written by this code. Bump it together with a new
+    /// step in `migrate` whenever the layout changes. The packed root is decoded
+    /// before any message runs, `migrate` included, so its fields are frozen:
+    /// new state has to go into `Lazy` or `Mapping` cells. Version 1 is the first
+    /// layout shipped with `set_code`; instances deployed before it, like the
+    /// original testnet one (plain `owner`, no `pending_owner`), can't decode
+    /// this root nor be upgraded in place and have to be redeployed.
+    pub const STORAGE_VERSION: u32 = 1;
+
+    /// Domain separator prepended to every signed authorization payload.
+    pub const SIGNATURE_DOMAIN: &[u8] = b"simple_counter";
+
+    /// Identifier of a counter hosted by the contract.
+    pub type CounterId = u32;
+
+    /// The original counter, backed by `number` and the contract-wide white list and roles.
+    pub const DEFAULT_COUNTER: CounterId = 0;
 
+    /// Maximum number of accounts accepted by the batch white list messages.
+    pub const MAX_BATCH_SIZE: u32 = 100;
+    /// Maximum number of entries returned by the paginated queries.
+    pub const MAX_PAGE_SIZE: u32 = 100;
+    /// Maximum number of milestones, since all of them are checked on every change.
+    pub const MAX_MILESTONES: u32 = 32;
+    /// Computation time granted to each `CounterSubscriber::on_milestone` call, so a
+    /// subscriber cannot spend the gas of the account changing the counter.
+    pub const SUBSCRIBER_REF_TIME_LIMIT: u64 = 5_000_000_000;
+    /// Proof size granted to each `CounterSubscriber::on_milestone` call.
+    pub const SUBSCRIBER_PROOF_SIZE_LIMIT: u64 = 256 * 1024;
+    /// Number of accounts kept in the contributors leaderboard.
+    pub const MAX_LEADERBOARD_SIZE: u32 = 20;
+    /// Approvals a reset needs until `set_reset_policy` is called.
+    pub const DEFAULT_RESET_THRESHOLD: u32 = 2;
+    /// Time (ms) a reset proposal stays open until `set_reset_policy` is called.
+    pub const DEFAULT_RESET_VALIDITY: Timestamp = 86_400_000;
 
     /// Defines the storage of your contract.
     /// Add new fields to the below struct in order
@@ -12,244 +180,2959 @@ mod simple_counter {
     pub struct SimpleCounter {
         /// Stores a single `bool` value on the storage.
         number: u128,
-        owner: AccountId,
+        /// `None` once the owner has renounced ownership.
+        owner: Option<AccountId>,
+        /// Account that must call `accept_ownership` to become the new owner.
+        pending_owner: Option<AccountId>,
         white_list: Mapping<AccountId, bool>,
+        /// Members of `white_list` by position, so the set can be enumerated.
+        white_list_members: Mapping<u32, AccountId>,
+        /// Position of each member inside `white_list_members`.
+        white_list_index: Mapping<AccountId, u32>,
+        white_list_len: u32,
+        /// Timestamp (ms) from which a white list entry is no longer valid.
+        /// Entries without expiry never lapse.
+        white_list_expiry: Mapping<AccountId, Timestamp>,
+        /// Role memberships, keyed by `(role, account)`.
+        roles: Mapping<(RoleId, AccountId), ()>,
+        /// Role allowed to grant and revoke each role. Defaults to `ADMIN_ROLE`.
+        role_admins: Mapping<RoleId, RoleId>,
+        /// Inclusive bounds enforced on every change of `number`.
+        min_value: u128,
+        max_value: u128,
+        /// Minimum time (ms) between two changes from the same account. `0` disables it.
+        cooldown: Timestamp,
+        /// Optional cap on the number of changes per account in a time window.
+        quota: Option<Quota>,
+        call_usage: Mapping<AccountId, CallUsage>,
+        /// Value of `number` at the end of each block where it changed, in block order.
+        checkpoints: Mapping<u32, Checkpoint>,
+        checkpoints_len: u32,
+        /// Additional counters created with `create_counter`.
+        counters: Mapping<CounterId, NamedCounter>,
+        /// Per-counter white lists, only used by restricted counters.
+        counter_white_list: Mapping<(CounterId, AccountId), ()>,
+        next_counter_id: CounterId,
+        /// While `true`, every message that changes state fails with `Paused`, except
+        /// `unpause` and the upgrade path (`set_code`, `migrate`). Queries keep working.
+        paused: bool,
+        /// Layout version of the data in storage. Kept in its own cell so bumping
+        /// it doesn't touch the root; a missing value means version 1.
+        storage_version: Lazy<u32>,
+        /// Next nonce expected from each signer of off-chain authorizations.
+        nonces: Mapping<AccountId, u64>,
+        /// Root of a Merkle tree of allowed accounts, as an alternative to `white_list`.
+        merkle_root: Lazy<Option<[u8; 32]>>,
+        /// Root under which each account proved its membership with `claim_access`.
+        /// Claims lapse when the root changes.
+        merkle_claims: Mapping<AccountId, [u8; 32]>,
+        /// Thresholds of `number` that emit `MilestoneReached` when crossed, sorted ascending.
+        milestones: Lazy<Vec<u128>>,
+        /// Contract implementing `CounterSubscriber`, notified of every milestone reached.
+        subscriber: Lazy<Option<AccountId>>,
+        /// Fee per call of `increase_by`/`decrease_by` (and their `Counter` versions).
+        fee: Lazy<Balance>,
+        /// When set, callers without permission can still increase by paying this fee.
+        public_fee: Lazy<Option<Balance>>,
+        /// Fees received and not yet withdrawn.
+        collected_fees: Lazy<Balance>,
+        /// Changes of the default counter made by each account.
+        stats: Mapping<AccountId, AccountStats>,
+        /// Accounts with the most changes and their totals, in descending order.
+        leaderboard: Lazy<Vec<(AccountId, u64)>>,
+        /// Approvals from `RESET_APPROVER_ROLE` holders needed to execute a reset.
+        reset_threshold: Lazy<u32>,
+        /// Time (ms) a reset proposal stays open after being proposed.
+        reset_validity: Lazy<Timestamp>,
+        /// Open reset proposal, replaced by a new `propose_reset`.
+        reset_proposal: Lazy<Option<ResetProposal>>,
+        next_reset_id: Lazy<u32>,
+    }
+
+    /// Action covered by a signed authorization.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+    pub enum SignedAction {
+        Increase,
+        Decrease,
+    }
+
+    /// A counter created with `create_counter`.
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct NamedCounter {
+        pub name: String,
+        pub value: u128,
+        /// Manages the counter's white list and can always change it.
+        pub admin: AccountId,
+        /// If `true`, only the admin and the counter's own white list can change it;
+        /// otherwise the contract-wide white list and roles apply.
+        pub restricted: bool,
+    }
+
+    /// Value of the counter from `block` onwards, until the next checkpoint.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Checkpoint {
+        pub block: BlockNumber,
+        pub value: u128,
+    }
+
+    /// Proposal to set `number` to `target_value`, executable once it gathers
+    /// enough approvals and before `deadline`.
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ResetProposal {
+        pub id: u32,
+        pub target_value: u128,
+        pub deadline: Timestamp,
+        /// Owner that proposed it, who cannot approve it.
+        pub proposer: AccountId,
+        /// Accounts that approved it. Only those still holding `RESET_APPROVER_ROLE`
+        /// count when it is executed.
+        pub approvers: Vec<AccountId>,
+    }
+
+    /// Increases and decreases of the default counter made by an account.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug, Default)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct AccountStats {
+        pub increments: u64,
+        pub decrements: u64,
+        /// Block of the last change, `0` if there was none.
+        pub last_block: BlockNumber,
+    }
+
+    /// At most `max_calls` changes per account every `window` milliseconds.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Quota {
+        pub max_calls: u32,
+        pub window: Timestamp,
+    }
+
+    /// Rate limiting bookkeeping of a single account.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct CallUsage {
+        pub last_call: Timestamp,
+        pub window_start: Timestamp,
+        pub calls_in_window: u32,
     }
 
     #[ink(event)]
     pub struct NewValue {
+        #[ink(topic)]
+        counter: CounterId,
         #[ink(topic)]
         sender: AccountId,
         new_number: u128,
+        /// Absolute difference with the previous value.
+        delta: u128,
+        /// `true` if the value went up, `false` if it went down or stayed the same.
+        increased: bool,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct CodeUpdated {
+        #[ink(topic)]
+        code_hash: Hash,
+    }
+
+    #[ink(event)]
+    pub struct Migrated {
+        from_version: u32,
+        to_version: u32,
+    }
+
+    #[ink(event)]
+    pub struct CounterCreated {
+        #[ink(topic)]
+        counter: CounterId,
+        name: String,
+        #[ink(topic)]
+        admin: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct WhitelistUpdated {
+        #[ink(topic)]
+        account: AccountId,
+        allowed: bool,
+    }
+
+    #[ink(event)]
+    pub struct MilestoneReached {
+        #[ink(topic)]
+        milestone: u128,
+        value: u128,
+        rising: bool,
+    }
+
+    #[ink(event)]
+    pub struct ResetProposed {
+        #[ink(topic)]
+        id: u32,
+        target_value: u128,
+        deadline: Timestamp,
     }
 
-    impl SimpleCounter {
-        /// Constructor that initializes the `bool` value to the given `init_value`.
-        #[ink(constructor)]
-        pub fn new() -> Self {
-            let caller = Self::env().caller();
-            Self {  number: 0,
-                    owner: caller,
-                    white_list: Mapping::new(), 
-                }
+    #[ink(event)]
+    pub struct ResetApproved {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        approver: AccountId,
+        approvals: u32,
+    }
+
+    #[ink(event)]
+    pub struct ResetExecuted {
+        #[ink(topic)]
+        id: u32,
+        target_value: u128,
+    }
+
+    #[ink(event)]
+    pub struct FeeCollected {
+        #[ink(topic)]
+        payer: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct FeesWithdrawn {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct MerkleRootUpdated {
+        root: Option<[u8; 32]>,
+    }
+
+    #[ink(event)]
+    pub struct AccessClaimed {
+        #[ink(topic)]
+        account: AccountId,
+        root: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct AccessExtended {
+        #[ink(topic)]
+        account: AccountId,
+        expires_at: Option<Timestamp>,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferStarted {
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferCancelled {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        pending_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous_owner: Option<AccountId>,
+        #[ink(topic)]
+        new_owner: Option<AccountId>,
+    }
+
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        role: RoleId,
+        #[ink(topic)]
+        account: AccountId,
+        sender: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleAdminChanged {
+        #[ink(topic)]
+        role: RoleId,
+        previous_admin_role: RoleId,
+        new_admin_role: RoleId,
+    }
+
+    impl SimpleCounter {
+        /// Constructor that initializes the `bool` value to the given `init_value`.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let caller = Self::env().caller();
+            let mut contract = Self {  number: 0,
+                    owner: Some(caller),
+                    pending_owner: None,
+                    white_list: Mapping::new(), 
+                    white_list_members: Mapping::new(),
+                    white_list_index: Mapping::new(),
+                    white_list_len: 0,
+                    white_list_expiry: Mapping::new(),
+                    roles: Mapping::new(),
+                    role_admins: Mapping::new(),
+                    min_value: 0,
+                    max_value: u128::MAX,
+                    cooldown: 0,
+                    quota: None,
+                    call_usage: Mapping::new(),
+                    checkpoints: Mapping::new(),
+                    checkpoints_len: 0,
+                    counters: Mapping::new(),
+                    counter_white_list: Mapping::new(),
+                    next_counter_id: DEFAULT_COUNTER + 1,
+                    paused: false,
+                    storage_version: Lazy::new(),
+                    nonces: Mapping::new(),
+                    merkle_root: Lazy::new(),
+                    merkle_claims: Mapping::new(),
+                    milestones: Lazy::new(),
+                    subscriber: Lazy::new(),
+                    fee: Lazy::new(),
+                    public_fee: Lazy::new(),
+                    collected_fees: Lazy::new(),
+                    stats: Mapping::new(),
+                    leaderboard: Lazy::new(),
+                    reset_threshold: Lazy::new(),
+                    reset_validity: Lazy::new(),
+                    reset_proposal: Lazy::new(),
+                    next_reset_id: Lazy::new(),
+                };
+            contract.storage_version.set(&STORAGE_VERSION);
+            contract.insert_role(ADMIN_ROLE, caller);
+            contract.write_checkpoint();
+            contract
+        }
+
+        /// Starts a two-step ownership transfer; `new_owner` has to call
+        /// `accept_ownership` to complete it.
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_owner()?;
+            self.pending_owner = Some(new_owner);
+            self.env().emit_event(OwnershipTransferStarted {
+                previous_owner: self.env().caller(),
+                new_owner,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            let pending_owner = self.pending_owner.ok_or(CounterError::NoPendingOwner)?;
+            if caller != pending_owner {
+                return Err(CounterError::NotPendingOwner);
+            }
+            let previous_owner = self.owner;
+            self.owner = Some(caller);
+            self.pending_owner = None;
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner: Some(caller),
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn cancel_ownership_transfer(&mut self) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_owner()?;
+            let pending_owner = self.pending_owner.take().ok_or(CounterError::NoPendingOwner)?;
+            self.env().emit_event(OwnershipTransferCancelled {
+                owner: self.env().caller(),
+                pending_owner,
+            });
+            Ok(())
+        }
+
+        /// Leaves the contract without owner. Roles keep working, but no one can
+        /// change the role admins or override them afterwards.
+        #[ink(message)]
+        pub fn renounce_ownership(&mut self) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_owner()?;
+            let previous_owner = self.owner.take();
+            self.pending_owner = None;
+            self.env().emit_event(OwnershipTransferred {
+                previous_owner,
+                new_owner: None,
+            });
+            Ok(())
+        }
+
+        /// Freezes every change of state until `unpause`. Callable by the owner or a pauser.
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<(), CounterError> {
+            self.ensure_pauser()?;
+            self.ensure_not_paused()?;
+            self.paused = true;
+            self.env().emit_event(Paused {
+                account: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<(), CounterError> {
+            self.ensure_pauser()?;
+            if !self.paused {
+                return Err(CounterError::NotPaused);
+            }
+            self.paused = false;
+            self.env().emit_event(Unpaused {
+                account: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        /// Replaces the contract code keeping its storage. Call `migrate` right
+        /// after if the new code uses a newer `STORAGE_VERSION`.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: Hash) -> Result<(), CounterError> {
+            self.ensure_owner()?;
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| CounterError::SetCodeFailed)?;
+            self.env().emit_event(CodeUpdated { code_hash });
+            Ok(())
+        }
+
+        /// Upgrades the data left by older code to `STORAGE_VERSION`, step by step.
+        /// `number`, `owner` and the white list are never touched. There are no
+        /// steps yet: version 1 is the first upgradable layout.
+        #[ink(message)]
+        pub fn migrate(&mut self) -> Result<(), CounterError> {
+            self.ensure_owner()?;
+            let from_version = self.storage_version();
+            if from_version >= STORAGE_VERSION {
+                return Err(CounterError::AlreadyMigrated);
+            }
+            self.storage_version.set(&STORAGE_VERSION);
+            self.env().emit_event(Migrated {
+                from_version,
+                to_version: STORAGE_VERSION,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn storage_version(&self) -> u32 {
+            self.storage_version.get().unwrap_or(1)
+        }
+
+        #[ink(message)]
+        pub fn owner(&self) -> Option<AccountId> {
+            self.owner
+        }
+
+        #[ink(message)]
+        pub fn pending_owner(&self) -> Option<AccountId> {
+            self.pending_owner
+        }
+
+        /// Grants `role` to `account`. The caller needs the admin role of `role`.
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: RoleId, account: AccountId) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_role_admin(role)?;
+            self.insert_role(role, account);
+            Ok(())
+        }
+
+        /// Revokes `role` from `account`. The caller needs the admin role of `role`.
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: RoleId, account: AccountId) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_role_admin(role)?;
+            self.delete_role(role, account);
+            Ok(())
+        }
+
+        /// Gives up a role held by the caller.
+        #[ink(message)]
+        pub fn renounce_role(&mut self, role: RoleId) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            let caller = self.env().caller();
+            self.ensure_role(role)?;
+            self.delete_role(role, caller);
+            Ok(())
+        }
+
+        /// Changes which role administers `role`. Only the owner can rearrange the hierarchy.
+        #[ink(message)]
+        pub fn set_role_admin(&mut self, role: RoleId, admin_role: RoleId) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_owner()?;
+            let previous_admin_role = self.get_role_admin(role);
+            self.role_admins.insert(role, &admin_role);
+            self.env().emit_event(RoleAdminChanged {
+                role,
+                previous_admin_role,
+                new_admin_role: admin_role,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn has_role(&self, role: RoleId, account: AccountId) -> bool {
+            self.roles.contains((role, account))
+        }
+
+        #[ink(message)]
+        pub fn get_role_admin(&self, role: RoleId) -> RoleId {
+            self.role_admins.get(role).unwrap_or(ADMIN_ROLE)
+        }
+
+        /// Adds `address` to the white list. With `expires_at` (block timestamp in ms)
+        /// the access lapses automatically; calling it again on a member replaces the
+        /// expiry and emits `AccessExtended`.
+        #[ink(message)]
+        pub fn add_to_white_list(&mut self, address: AccountId, expires_at: Option<Timestamp>) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_role(ADMIN_ROLE)?;
+            self.ensure_valid_expiry(expires_at)?;
+            self.grant_access(address, expires_at);
+            Ok(())
+        }
+
+        /// Replaces the expiry of an existing entry; `None` makes it permanent.
+        #[ink(message)]
+        pub fn extend_access(&mut self, address: AccountId, expires_at: Option<Timestamp>) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_role(ADMIN_ROLE)?;
+            if !self.white_list.contains(address) {
+                return Err(CounterError::NotWhitelisted);
+            }
+            self.ensure_valid_expiry(expires_at)?;
+            self.set_expiry(address, expires_at);
+            self.env().emit_event(AccessExtended {
+                account: address,
+                expires_at,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn access_expires_at(&self, account: AccountId) -> Option<Timestamp> {
+            self.white_list_expiry.get(account)
+        }
+
+        #[ink(message)]
+        pub fn remove_from_white_list(&mut self, address: AccountId) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_role(ADMIN_ROLE)?;
+            self.delete_white_list(address);
+            Ok(())
+        }
+
+        /// Gives permanent access to every address, like `add_to_white_list(address, None)`:
+        /// members with an expiry become permanent.
+        #[ink(message)]
+        pub fn add_many_to_white_list(&mut self, addresses: Vec<AccountId>) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_role(ADMIN_ROLE)?;
+            Self::ensure_batch_size(&addresses)?;
+            for address in addresses {
+                self.grant_access(address, None);
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn remove_many_from_white_list(&mut self, addresses: Vec<AccountId>) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_role(ADMIN_ROLE)?;
+            Self::ensure_batch_size(&addresses)?;
+            for address in addresses {
+                self.delete_white_list(address);
+            }
+            Ok(())
+        }
+
+        /// `false` for accounts whose entry has expired. Includes accounts that
+        /// claimed access under the current Merkle root.
+        #[ink(message)]
+        pub fn is_whitelisted(&self, account: AccountId) -> bool {
+            self.ensure_white_list(account).is_ok()
+        }
+
+        /// Publishes the root of a Merkle tree whose leaves are the Blake2x256 hashes of
+        /// the allowed account ids. Pairs are hashed sorted, so proofs need no direction.
+        /// Publishing a new root (or `None`) invalidates previous claims.
+        #[ink(message)]
+        pub fn set_merkle_root(&mut self, root: Option<[u8; 32]>) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_owner()?;
+            self.merkle_root.set(&root);
+            self.env().emit_event(MerkleRootUpdated { root });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn merkle_root(&self) -> Option<[u8; 32]> {
+            self.merkle_root.get().flatten()
+        }
+
+        /// Proves that the caller is a leaf of the published Merkle tree and grants it
+        /// access until the root changes.
+        #[ink(message)]
+        pub fn claim_access(&mut self, proof: Vec<[u8; 32]>) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            let root = self.merkle_root().ok_or(CounterError::NoMerkleRoot)?;
+            let caller = self.env().caller();
+            let mut node = self.env().hash_bytes::<Blake2x256>(caller.as_ref());
+            for sibling in proof {
+                let (first, second) = if node <= sibling { (node, sibling) } else { (sibling, node) };
+                let mut pair = [0u8; 64];
+                pair[..32].copy_from_slice(&first);
+                pair[32..].copy_from_slice(&second);
+                node = self.env().hash_bytes::<Blake2x256>(&pair);
+            }
+            if node != root {
+                return Err(CounterError::InvalidProof);
+            }
+            self.merkle_claims.insert(caller, &root);
+            self.env().emit_event(AccessClaimed { account: caller, root });
+            Ok(())
+        }
+
+        /// Expired entries are still counted until they are removed.
+        #[ink(message)]
+        pub fn whitelist_len(&self) -> u32 {
+            self.white_list_len
+        }
+
+        /// Returns up to `limit` whitelisted accounts starting at `offset`.
+        /// `limit` is capped at `MAX_PAGE_SIZE`, and the order changes when
+        /// accounts are removed.
+        #[ink(message)]
+        pub fn whitelist_page(&self, offset: u32, limit: u32) -> Vec<AccountId> {
+            let end = offset
+                .saturating_add(limit.min(MAX_PAGE_SIZE))
+                .min(self.white_list_len);
+            (offset..end)
+                .filter_map(|index| self.white_list_members.get(index))
+                .collect()
+        }
+
+        /// The caller has to transfer at least the fee, or the public fee if it is
+        /// not allowed to increase. Anything transferred is kept as fee.
+        #[ink(message, payable)]
+        pub fn increase_by(&mut self, amount: u128) -> Result<(), CounterError> {
+            let caller = self.env().caller();
+            self.ensure_not_paused()?;
+            let fee = self.fee_for(caller, INCREMENTER_ROLE, true)?;
+            self.ensure_fee_paid(fee)?;
+            self.apply_increase(caller, amount)?;
+            self.collect_fee(caller);
+            Ok(())
+        }
+
+        #[ink(message, payable)]
+        pub fn decrease_by(&mut self, amount: u128) -> Result<(), CounterError> {
+            let caller = self.env().caller();
+            self.ensure_not_paused()?;
+            let fee = self.fee_for(caller, DECREMENTER_ROLE, false)?;
+            self.ensure_fee_paid(fee)?;
+            self.apply_decrease(caller, amount)?;
+            self.collect_fee(caller);
+            Ok(())
+        }
+
+        /// Sets the fee per call and the optional public fee, which has to be higher.
+        #[ink(message)]
+        pub fn set_fees(&mut self, fee: Balance, public_fee: Option<Balance>) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_owner()?;
+            if public_fee.is_some_and(|public_fee| public_fee <= fee) {
+                return Err(CounterError::InvalidFees);
+            }
+            self.fee.set(&fee);
+            self.public_fee.set(&public_fee);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn fees(&self) -> (Balance, Option<Balance>) {
+            (self.fee.get_or_default(), self.public_fee.get().flatten())
+        }
+
+        #[ink(message)]
+        pub fn collected_fees(&self) -> Balance {
+            self.collected_fees.get_or_default()
+        }
+
+        #[ink(message)]
+        pub fn withdraw_fees(&mut self, to: AccountId, amount: Balance) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_owner()?;
+            let collected_fees = self.collected_fees();
+            if amount > collected_fees {
+                return Err(CounterError::InsufficientFees);
+            }
+            self.env()
+                .transfer(to, amount)
+                .map_err(|_| CounterError::TransferFailed)?;
+            self.collected_fees.set(&(collected_fees - amount));
+            self.env().emit_event(FeesWithdrawn { to, amount });
+            Ok(())
+        }
+
+        /// Increases the counter by one on behalf of `signer`, so a relayer can pay the fees.
+        /// `signature` is an ECDSA signature over `signature_payload_hash`. The relayer
+        /// pays the regular fee; the public fee does not apply, since `signer` needs permission.
+        #[ink(message, payable)]
+        pub fn increase_with_signature(&mut self, signer: AccountId, nonce: u64, deadline: Timestamp, signature: [u8; 65]) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_signature(SignedAction::Increase, signer, nonce, deadline, &signature)?;
+            self.ensure_fee_paid(self.fee.get_or_default())?;
+            self.increase_as(signer, 1)?;
+            self.nonces.insert(signer, &(nonce + 1));
+            self.collect_fee(self.env().caller());
+            Ok(())
+        }
+
+        /// Decreases the counter by one on behalf of `signer`. See `increase_with_signature`.
+        #[ink(message, payable)]
+        pub fn decrease_with_signature(&mut self, signer: AccountId, nonce: u64, deadline: Timestamp, signature: [u8; 65]) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_signature(SignedAction::Decrease, signer, nonce, deadline, &signature)?;
+            self.ensure_fee_paid(self.fee.get_or_default())?;
+            self.decrease_as(signer, 1)?;
+            self.nonces.insert(signer, &(nonce + 1));
+            self.collect_fee(self.env().caller());
+            Ok(())
+        }
+
+        /// Next nonce `signer` has to use.
+        #[ink(message)]
+        pub fn nonce_of(&self, signer: AccountId) -> u64 {
+            self.nonces.get(signer).unwrap_or(0)
+        }
+
+        /// Blake2x256 hash of the SCALE-encoded
+        /// `(SIGNATURE_DOMAIN, contract, action, signer, nonce, deadline)`, which is what
+        /// the signer has to sign.
+        #[ink(message)]
+        pub fn signature_payload_hash(&self, action: SignedAction, signer: AccountId, nonce: u64, deadline: Timestamp) -> [u8; 32] {
+            let payload = (SIGNATURE_DOMAIN, self.env().account_id(), action, signer, nonce, deadline);
+            self.env().hash_encoded::<Blake2x256, _>(&payload)
+        }
+
+        /// Overrides the counter value. Still subject to the configured bounds.
+        #[ink(message)]
+        pub fn set_number(&mut self, value: u128) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_role(ADMIN_ROLE)?;
+            self.update_number(self.env().caller(), value)
+        }
+
+        #[ink(message)]
+        pub fn set_bounds(&mut self, min_value: u128, max_value: u128) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_owner()?;
+            if min_value > max_value || self.number < min_value || self.number > max_value {
+                return Err(CounterError::InvalidBounds);
+            }
+            self.min_value = min_value;
+            self.max_value = max_value;
+            Ok(())
+        }
+
+        /// Configures the per-account rate limit applied to `increase_*`/`decrease_*`.
+        /// A `cooldown` of `0` and no `quota` disable it.
+        #[ink(message)]
+        pub fn set_rate_limit(&mut self, cooldown: Timestamp, quota: Option<Quota>) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_owner()?;
+            if let Some(quota) = quota {
+                if quota.max_calls == 0 || quota.window == 0 {
+                    return Err(CounterError::InvalidRateLimit);
+                }
+            }
+            self.cooldown = cooldown;
+            self.quota = quota;
+            Ok(())
+        }
+
+        /// Returns `(cooldown, quota)`.
+        #[ink(message)]
+        pub fn rate_limit(&self) -> (Timestamp, Option<Quota>) {
+            (self.cooldown, self.quota)
+        }
+
+        /// Earliest block timestamp at which `account` can change the counter again.
+        /// `0` if the account has never been limited.
+        #[ink(message)]
+        pub fn next_allowed_call(&self, account: AccountId) -> Timestamp {
+            let Some(usage) = self.call_usage.get(account) else {
+                return 0;
+            };
+            let mut next = usage.last_call.saturating_add(self.cooldown);
+            if let Some(quota) = self.quota {
+                if usage.calls_in_window >= quota.max_calls {
+                    next = next.max(usage.window_start.saturating_add(quota.window));
+                }
+            }
+            next
+        }
+
+        /// Sets how many approvals a reset needs and how long (ms) a proposal stays open.
+        #[ink(message)]
+        pub fn set_reset_policy(&mut self, threshold: u32, validity: Timestamp) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_owner()?;
+            if threshold == 0 || validity == 0 {
+                return Err(CounterError::InvalidResetPolicy);
+            }
+            self.reset_threshold.set(&threshold);
+            self.reset_validity.set(&validity);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn reset_policy(&self) -> (u32, Timestamp) {
+            (
+                self.reset_threshold.get().unwrap_or(DEFAULT_RESET_THRESHOLD),
+                self.reset_validity.get().unwrap_or(DEFAULT_RESET_VALIDITY),
+            )
+        }
+
+        /// Proposes setting the counter to `target_value`, replacing any open proposal.
+        #[ink(message)]
+        pub fn propose_reset(&mut self, target_value: u128) -> Result<u32, CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_owner()?;
+            if target_value < self.min_value || target_value > self.max_value {
+                return Err(CounterError::OutOfBounds);
+            }
+            let id = self.next_reset_id.get_or_default();
+            let (_, validity) = self.reset_policy();
+            let deadline = self.env().block_timestamp().saturating_add(validity);
+            self.next_reset_id.set(&id.wrapping_add(1));
+            self.reset_proposal.set(&Some(ResetProposal {
+                id,
+                target_value,
+                deadline,
+                proposer: self.env().caller(),
+                approvers: Vec::new(),
+            }));
+            self.env().emit_event(ResetProposed { id, target_value, deadline });
+            Ok(id)
+        }
+
+        #[ink(message)]
+        pub fn approve_reset(&mut self, id: u32) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_role(RESET_APPROVER_ROLE)?;
+            let mut proposal = self.ensure_open_reset(id)?;
+            let approver = self.env().caller();
+            if approver == proposal.proposer {
+                return Err(CounterError::ProposerCannotApprove);
+            }
+            if proposal.approvers.contains(&approver) {
+                return Err(CounterError::AlreadyApproved);
+            }
+            proposal.approvers.push(approver);
+            let approvals = proposal.approvers.len() as u32;
+            self.reset_proposal.set(&Some(proposal));
+            self.env().emit_event(ResetApproved { id, approver, approvals });
+            Ok(())
+        }
+
+        /// Applies an approved reset. Anyone can call it once the threshold is met,
+        /// counting only approvers that still hold `RESET_APPROVER_ROLE`.
+        #[ink(message)]
+        pub fn execute_reset(&mut self, id: u32) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            let proposal = self.ensure_open_reset(id)?;
+            let approvals = proposal
+                .approvers
+                .iter()
+                .filter(|approver| self.has_role(RESET_APPROVER_ROLE, **approver))
+                .count();
+            let (threshold, _) = self.reset_policy();
+            if approvals < threshold as usize {
+                return Err(CounterError::NotEnoughApprovals);
+            }
+            self.update_number(self.env().caller(), proposal.target_value)?;
+            self.reset_proposal.set(&None);
+            self.env().emit_event(ResetExecuted {
+                id,
+                target_value: proposal.target_value,
+            });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn reset_proposal(&self) -> Option<ResetProposal> {
+            self.reset_proposal.get().flatten()
+        }
+
+        #[ink(message)]
+        pub fn add_milestone(&mut self, milestone: u128) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_owner()?;
+            let mut milestones = self.milestones.get_or_default();
+            let index = match milestones.binary_search(&milestone) {
+                Ok(_) => return Err(CounterError::MilestoneExists),
+                Err(index) => index,
+            };
+            if milestones.len() >= MAX_MILESTONES as usize {
+                return Err(CounterError::TooManyMilestones);
+            }
+            milestones.insert(index, milestone);
+            self.milestones.set(&milestones);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn remove_milestone(&mut self, milestone: u128) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_owner()?;
+            let mut milestones = self.milestones.get_or_default();
+            let index = milestones
+                .binary_search(&milestone)
+                .map_err(|_| CounterError::UnknownMilestone)?;
+            milestones.remove(index);
+            self.milestones.set(&milestones);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn milestones(&self) -> Vec<u128> {
+            self.milestones.get_or_default()
+        }
+
+        /// Registers the contract whose `CounterSubscriber::on_milestone` is called for
+        /// every milestone reached. Each callback runs with at most
+        /// `SUBSCRIBER_REF_TIME_LIMIT`/`SUBSCRIBER_PROOF_SIZE_LIMIT`; if it fails or runs
+        /// out of gas the change still goes through.
+        #[ink(message)]
+        pub fn set_subscriber(&mut self, subscriber: Option<AccountId>) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_owner()?;
+            self.subscriber.set(&subscriber);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn subscriber(&self) -> Option<AccountId> {
+            self.subscriber.get().flatten()
+        }
+
+        /// Value the counter had at the end of `block`, or `None` if `block` is
+        /// older than the contract.
+        #[ink(message)]
+        pub fn value_at(&self, block: BlockNumber) -> Option<u128> {
+            // Binary search for the last checkpoint with `checkpoint.block <= block`.
+            let mut low = 0;
+            let mut high = self.checkpoints_len;
+            while low < high {
+                let mid = low + (high - low) / 2;
+                match self.checkpoints.get(mid) {
+                    Some(checkpoint) if checkpoint.block <= block => low = mid + 1,
+                    _ => high = mid,
+                }
+            }
+            if low == 0 {
+                return None;
+            }
+            self.checkpoints.get(low - 1).map(|checkpoint| checkpoint.value)
+        }
+
+        /// Returns up to `limit` checkpoints, oldest first, starting at `offset`.
+        /// `limit` is capped at `MAX_PAGE_SIZE`.
+        #[ink(message)]
+        pub fn history(&self, offset: u32, limit: u32) -> Vec<Checkpoint> {
+            let end = offset
+                .saturating_add(limit.min(MAX_PAGE_SIZE))
+                .min(self.checkpoints_len);
+            (offset..end)
+                .filter_map(|index| self.checkpoints.get(index))
+                .collect()
+        }
+
+        #[ink(message)]
+        pub fn history_len(&self) -> u32 {
+            self.checkpoints_len
+        }
+
+        /// Returns `(min_value, max_value)`.
+        #[ink(message)]
+        pub fn bounds(&self) -> (u128, u128) {
+            (self.min_value, self.max_value)
+        }
+
+        /// Creates a new counter starting at zero and returns its id.
+        #[ink(message)]
+        pub fn create_counter(&mut self, name: String, admin: AccountId, restricted: bool) -> Result<CounterId, CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_role(ADMIN_ROLE)?;
+            let counter = self.next_counter_id;
+            self.next_counter_id = counter.checked_add(1).ok_or(CounterError::Overflow)?;
+            self.counters.insert(counter, &NamedCounter {
+                name: name.clone(),
+                value: 0,
+                admin,
+                restricted,
+            });
+            self.env().emit_event(CounterCreated { counter, name, admin });
+            Ok(counter)
+        }
+
+        /// Adds or removes `account` from the white list of a restricted counter.
+        #[ink(message)]
+        pub fn set_counter_access(&mut self, counter: CounterId, account: AccountId, allowed: bool) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_counter_admin(counter)?;
+            if allowed {
+                self.counter_white_list.insert((counter, account), &());
+            } else {
+                self.counter_white_list.remove((counter, account));
+            }
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn set_counter_admin(&mut self, counter: CounterId, admin: AccountId) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            let mut named = self.ensure_counter_admin(counter)?;
+            named.admin = admin;
+            self.counters.insert(counter, &named);
+            Ok(())
+        }
+
+        /// Increases any counter by one. `DEFAULT_COUNTER` behaves like `increase_number`,
+        /// fees included. Named counters charge no fee; any value sent is kept as fees.
+        #[ink(message, payable)]
+        pub fn increase(&mut self, counter: CounterId) -> Result<(), CounterError> {
+            if counter == DEFAULT_COUNTER {
+                return self.increase_number();
+            }
+            self.ensure_not_paused()?;
+            let mut named = self.ensure_can_change_counter(counter, INCREMENTER_ROLE)?;
+            let caller = self.env().caller();
+            self.ensure_rate_limit(caller)?;
+            named.value = named.value.checked_add(1).ok_or(CounterError::Overflow)?;
+            self.update_counter(counter, named, true);
+            self.collect_fee(caller);
+            Ok(())
+        }
+
+        /// Decreases any counter by one. `DEFAULT_COUNTER` behaves like `decrease_number`.
+        /// See `increase`.
+        #[ink(message, payable)]
+        pub fn decrease(&mut self, counter: CounterId) -> Result<(), CounterError> {
+            if counter == DEFAULT_COUNTER {
+                return self.decrease_number();
+            }
+            self.ensure_not_paused()?;
+            let mut named = self.ensure_can_change_counter(counter, DECREMENTER_ROLE)?;
+            let caller = self.env().caller();
+            self.ensure_rate_limit(caller)?;
+            named.value = named.value.checked_sub(1).ok_or(CounterError::Underflow)?;
+            self.update_counter(counter, named, false);
+            self.collect_fee(caller);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn retrieve(&self, counter: CounterId) -> Option<u128> {
+            if counter == DEFAULT_COUNTER {
+                return Some(self.number);
+            }
+            self.counters.get(counter).map(|named| named.value)
+        }
+
+        #[ink(message)]
+        pub fn counter_info(&self, counter: CounterId) -> Option<NamedCounter> {
+            self.counters.get(counter)
+        }
+
+        #[ink(message)]
+        pub fn stats_of(&self, account: AccountId) -> AccountStats {
+            self.stats.get(account).unwrap_or_default()
+        }
+
+        /// Up to `n` accounts with the most changes, in descending order.
+        /// Only the top `MAX_LEADERBOARD_SIZE` accounts are tracked.
+        #[ink(message)]
+        pub fn top_contributors(&self, n: u32) -> Vec<(AccountId, AccountStats)> {
+            self.leaderboard
+                .get_or_default()
+                .into_iter()
+                .take(n as usize)
+                .map(|(account, _)| (account, self.stats_of(account)))
+                .collect()
+        }
+
+
+        fn ensure_owner(&self) -> Result<(), CounterError> {
+            if Some(self.env().caller()) != self.owner {
+                return Err(CounterError::NotOwner);
+            }
+            Ok(())
+        }
+
+        fn ensure_role(&self, role: RoleId) -> Result<(), CounterError> {
+            if !self.has_role(role, self.env().caller()) {
+                return Err(CounterError::MissingRole);
+            }
+            Ok(())
+        }
+
+        fn ensure_pauser(&self) -> Result<(), CounterError> {
+            if self.ensure_owner().is_ok() {
+                return Ok(());
+            }
+            self.ensure_role(PAUSER_ROLE)
+        }
+
+        fn ensure_not_paused(&self) -> Result<(), CounterError> {
+            if self.paused {
+                return Err(CounterError::Paused);
+            }
+            Ok(())
+        }
+
+        /// The owner can always manage roles, so a lost admin set can be restored.
+        fn ensure_role_admin(&self, role: RoleId) -> Result<(), CounterError> {
+            if self.ensure_owner().is_ok() {
+                return Ok(());
+            }
+            self.ensure_role(self.get_role_admin(role))
+        }
+
+        /// An explicit `white_list` entry always decides (including its expiry);
+        /// otherwise a claim under the current Merkle root grants access.
+        fn ensure_white_list(&self, account: AccountId) -> Result<(), CounterError> {
+            if self.white_list.get(account).unwrap_or(false) {
+                if self.is_expired(account) {
+                    return Err(CounterError::AccessExpired);
+                }
+                return Ok(());
+            }
+            match (self.merkle_root(), self.merkle_claims.get(account)) {
+                (Some(root), Some(claimed)) if root == claimed => Ok(()),
+                _ => Err(CounterError::NotWhitelisted),
+            }
+        }
+
+        fn ensure_valid_expiry(&self, expires_at: Option<Timestamp>) -> Result<(), CounterError> {
+            match expires_at {
+                Some(expires_at) if expires_at <= self.env().block_timestamp() => {
+                    Err(CounterError::InvalidExpiry)
+                }
+                _ => Ok(()),
+            }
+        }
+
+        fn is_expired(&self, account: AccountId) -> bool {
+            self.white_list_expiry
+                .get(account)
+                .is_some_and(|expires_at| self.env().block_timestamp() >= expires_at)
+        }
+
+        /// Adds `account` with the given expiry, or replaces the expiry of an existing member.
+        fn grant_access(&mut self, account: AccountId, expires_at: Option<Timestamp>) {
+            if !self.white_list.contains(account) {
+                self.insert_white_list(account);
+                self.set_expiry(account, expires_at);
+                return;
+            }
+            if self.white_list_expiry.get(account) != expires_at {
+                self.set_expiry(account, expires_at);
+                self.env().emit_event(AccessExtended { account, expires_at });
+            }
+        }
+
+        fn set_expiry(&mut self, account: AccountId, expires_at: Option<Timestamp>) {
+            if let Some(expires_at) = expires_at {
+                self.white_list_expiry.insert(account, &expires_at);
+            } else {
+                self.white_list_expiry.remove(account);
+            }
+        }
+
+        /// Whitelisted accounts can both increase and decrease; otherwise the
+        /// account needs the specific `role`.
+        fn ensure_can_change(&self, account: AccountId, role: RoleId) -> Result<(), CounterError> {
+            if self.has_role(role, account) {
+                return Ok(());
+            }
+            self.ensure_white_list(account)
+        }
+
+        fn ensure_batch_size(addresses: &[AccountId]) -> Result<(), CounterError> {
+            if addresses.len() > MAX_BATCH_SIZE as usize {
+                return Err(CounterError::BatchTooLarge);
+            }
+            Ok(())
+        }
+
+        fn insert_white_list(&mut self, account: AccountId) {
+            if self.white_list.contains(account) {
+                return;
+            }
+            self.white_list.insert(account, &true);
+            self.white_list_members.insert(self.white_list_len, &account);
+            self.white_list_index.insert(account, &self.white_list_len);
+            self.white_list_len += 1;
+            self.env().emit_event(WhitelistUpdated { account, allowed: true });
+        }
+
+        /// Removes the entry instead of storing `false`, so the storage deposit is refunded.
+        /// The last member is moved into the freed position to keep the list compact.
+        fn delete_white_list(&mut self, account: AccountId) {
+            if !self.white_list.contains(account) {
+                return;
+            }
+            self.white_list.remove(account);
+            self.white_list_expiry.remove(account);
+            let index = self.white_list_index.take(account).unwrap_or_default();
+            let last_index = self.white_list_len - 1;
+            if index != last_index {
+                if let Some(last) = self.white_list_members.get(last_index) {
+                    self.white_list_members.insert(index, &last);
+                    self.white_list_index.insert(last, &index);
+                }
+            }
+            self.white_list_members.remove(last_index);
+            self.white_list_len = last_index;
+            self.env().emit_event(WhitelistUpdated { account, allowed: false });
+        }
+
+        fn ensure_rate_limit(&self, account: AccountId) -> Result<(), CounterError> {
+            if self.env().block_timestamp() < self.next_allowed_call(account) {
+                return Err(CounterError::RateLimited);
+            }
+            Ok(())
+        }
+
+        fn record_call(&mut self, account: AccountId) {
+            if self.cooldown == 0 && self.quota.is_none() {
+                return;
+            }
+            let now = self.env().block_timestamp();
+            let usage = match (self.call_usage.get(account), self.quota) {
+                (Some(usage), Some(quota)) if now < usage.window_start.saturating_add(quota.window) => {
+                    CallUsage {
+                        last_call: now,
+                        window_start: usage.window_start,
+                        calls_in_window: usage.calls_in_window.saturating_add(1),
+                    }
+                }
+                _ => CallUsage {
+                    last_call: now,
+                    window_start: now,
+                    calls_in_window: 1,
+                },
+            };
+            self.call_usage.insert(account, &usage);
+        }
+
+        /// Checks an authorization signed with the ECDSA key of `signer`, whose account id
+        /// is the Blake2x256 hash of the compressed public key.
+        fn ensure_signature(&self, action: SignedAction, signer: AccountId, nonce: u64, deadline: Timestamp, signature: &[u8; 65]) -> Result<(), CounterError> {
+            if self.env().block_timestamp() > deadline {
+                return Err(CounterError::SignatureExpired);
+            }
+            if nonce != self.nonce_of(signer) {
+                return Err(CounterError::InvalidNonce);
+            }
+            let message_hash = self.signature_payload_hash(action, signer, nonce, deadline);
+            let public_key = self
+                .env()
+                .ecdsa_recover(signature, &message_hash)
+                .map_err(|_| CounterError::InvalidSignature)?;
+            let recovered = AccountId::from(self.env().hash_bytes::<Blake2x256>(&public_key));
+            if recovered != signer {
+                return Err(CounterError::InvalidSignature);
+            }
+            Ok(())
+        }
+
+        /// Increases `number` on behalf of `account`, which may differ from the
+        /// caller when a relayer submits a signed authorization.
+        fn increase_as(&mut self, account: AccountId, amount: u128) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_can_change(account, INCREMENTER_ROLE)?;
+            self.apply_increase(account, amount)
+        }
+
+        fn decrease_as(&mut self, account: AccountId, amount: u128) -> Result<(), CounterError> {
+            self.ensure_not_paused()?;
+            self.ensure_can_change(account, DECREMENTER_ROLE)?;
+            self.apply_decrease(account, amount)
+        }
+
+        /// Fee `account` has to pay to change the counter. Without permission the
+        /// public fee applies, if set and `allow_public` (only increases use it).
+        fn fee_for(&self, account: AccountId, role: RoleId, allow_public: bool) -> Result<Balance, CounterError> {
+            match self.ensure_can_change(account, role) {
+                Ok(()) => Ok(self.fee.get_or_default()),
+                Err(error) => match self.public_fee.get().flatten() {
+                    Some(public_fee) if allow_public => Ok(public_fee),
+                    _ => Err(error),
+                },
+            }
+        }
+
+        fn ensure_open_reset(&self, id: u32) -> Result<ResetProposal, CounterError> {
+            let proposal = self
+                .reset_proposal()
+                .filter(|proposal| proposal.id == id)
+                .ok_or(CounterError::NoResetProposal)?;
+            if self.env().block_timestamp() > proposal.deadline {
+                return Err(CounterError::ResetProposalExpired);
+            }
+            Ok(proposal)
+        }
+
+        fn ensure_fee_paid(&self, fee: Balance) -> Result<(), CounterError> {
+            if self.env().transferred_value() < fee {
+                return Err(CounterError::FeeTooLow);
+            }
+            Ok(())
+        }
+
+        fn collect_fee(&mut self, payer: AccountId) {
+            let amount = self.env().transferred_value();
+            if amount > 0 {
+                self.collected_fees.set(&self.collected_fees().saturating_add(amount));
+                self.env().emit_event(FeeCollected { payer, amount });
+            }
+        }
+
+        /// Applies an already authorized increase: rate limit, bounds and bookkeeping.
+        fn apply_increase(&mut self, account: AccountId, amount: u128) -> Result<(), CounterError> {
+            self.ensure_rate_limit(account)?;
+            let new_number = self
+                .number
+                .checked_add(amount)
+                .ok_or(CounterError::Overflow)?;
+            self.update_number(account, new_number)?;
+            self.record_call(account);
+            self.record_contribution(account, true);
+            Ok(())
+        }
+
+        fn apply_decrease(&mut self, account: AccountId, amount: u128) -> Result<(), CounterError> {
+            self.ensure_rate_limit(account)?;
+            let new_number = self
+                .number
+                .checked_sub(amount)
+                .ok_or(CounterError::Underflow)?;
+            self.update_number(account, new_number)?;
+            self.record_call(account);
+            self.record_contribution(account, false);
+            Ok(())
+        }
+
+        fn update_number(&mut self, sender: AccountId, new_number: u128) -> Result<(), CounterError> {
+            if new_number < self.min_value || new_number > self.max_value {
+                return Err(CounterError::OutOfBounds);
+            }
+            let previous_number = self.number;
+            self.number = new_number;
+            self.write_checkpoint();
+            self.env().emit_event(NewValue {
+                counter: DEFAULT_COUNTER,
+                sender,
+                new_number,
+                delta: new_number.abs_diff(previous_number),
+                increased: new_number > previous_number,
+            });
+            self.notify_milestones(previous_number, new_number);
+            Ok(())
+        }
+
+        fn notify_milestones(&mut self, previous_number: u128, new_number: u128) {
+            let rising = new_number > previous_number;
+            let (low, high) = if rising {
+                (previous_number, new_number)
+            } else {
+                (new_number, previous_number)
+            };
+            // A milestone `m` is crossed when one side is below `m` and the other is at least `m`.
+            let crossed: Vec<u128> = self
+                .milestones
+                .get_or_default()
+                .into_iter()
+                .filter(|milestone| low < *milestone && *milestone <= high)
+                .collect();
+            for milestone in crossed {
+                self.env().emit_event(MilestoneReached {
+                    milestone,
+                    value: new_number,
+                    rising,
+                });
+                if let Some(subscriber) = self.subscriber() {
+                    let mut subscriber: ink::contract_ref!(CounterSubscriber) = subscriber.into();
+                    // The result is ignored on purpose: a failing subscriber must not revert the change.
+                    let _ = TraitCallBuilder::call_mut(&mut subscriber)
+                        .on_milestone(milestone, new_number, rising)
+                        .ref_time_limit(SUBSCRIBER_REF_TIME_LIMIT)
+                        .proof_size_limit(SUBSCRIBER_PROOF_SIZE_LIMIT)
+                        .try_invoke();
+                }
+            }
+        }
+
+        fn ensure_counter_admin(&self, counter: CounterId) -> Result<NamedCounter, CounterError> {
+            let named = self.counters.get(counter).ok_or(CounterError::UnknownCounter)?;
+            if self.env().caller() != named.admin {
+                return Err(CounterError::NotCounterAdmin);
+            }
+            Ok(named)
+        }
+
+        fn ensure_can_change_counter(&self, counter: CounterId, role: RoleId) -> Result<NamedCounter, CounterError> {
+            let named = self.counters.get(counter).ok_or(CounterError::UnknownCounter)?;
+            let caller = self.env().caller();
+            if !named.restricted {
+                self.ensure_can_change(caller, role)?;
+            } else if caller != named.admin && !self.counter_white_list.contains((counter, caller)) {
+                return Err(CounterError::NotWhitelisted);
+            }
+            Ok(named)
+        }
+
+        fn update_counter(&mut self, counter: CounterId, named: NamedCounter, increased: bool) {
+            let caller = self.env().caller();
+            self.counters.insert(counter, &named);
+            self.record_call(caller);
+            self.env().emit_event(NewValue {
+                counter,
+                sender: caller,
+                new_number: named.value,
+                delta: 1,
+                increased,
+            });
+        }
+
+        fn record_contribution(&mut self, account: AccountId, increased: bool) {
+            let mut stats = self.stats_of(account);
+            if increased {
+                stats.increments = stats.increments.saturating_add(1);
+            } else {
+                stats.decrements = stats.decrements.saturating_add(1);
+            }
+            stats.last_block = self.env().block_number();
+            self.stats.insert(account, &stats);
+            self.update_leaderboard(account, stats.increments.saturating_add(stats.decrements));
+        }
+
+        /// Moves `account` to its place for `total`. Ties keep the account that got there first.
+        fn update_leaderboard(&mut self, account: AccountId, total: u64) {
+            let mut leaderboard = self.leaderboard.get_or_default();
+            if let Some(index) = leaderboard.iter().position(|(member, _)| *member == account) {
+                leaderboard.remove(index);
+            }
+            let index = leaderboard
+                .iter()
+                .position(|(_, member_total)| *member_total < total)
+                .unwrap_or(leaderboard.len());
+            if index < MAX_LEADERBOARD_SIZE as usize {
+                leaderboard.insert(index, (account, total));
+                leaderboard.truncate(MAX_LEADERBOARD_SIZE as usize);
+            }
+            self.leaderboard.set(&leaderboard);
+        }
+
+        /// Records the current value, overwriting the last checkpoint if it is
+        /// from the current block.
+        fn write_checkpoint(&mut self) {
+            let checkpoint = Checkpoint {
+                block: self.env().block_number(),
+                value: self.number,
+            };
+            if let Some(last_index) = self.checkpoints_len.checked_sub(1) {
+                let last = self.checkpoints.get(last_index);
+                if last.is_some_and(|last| last.block == checkpoint.block) {
+                    self.checkpoints.insert(last_index, &checkpoint);
+                    return;
+                }
+            }
+            self.checkpoints.insert(self.checkpoints_len, &checkpoint);
+            self.checkpoints_len += 1;
+        }
+
+        fn insert_role(&mut self, role: RoleId, account: AccountId) {
+            if self.has_role(role, account) {
+                return;
+            }
+            self.roles.insert((role, account), &());
+            self.env().emit_event(RoleGranted {
+                role,
+                account,
+                sender: self.env().caller(),
+            });
+        }
+
+        fn delete_role(&mut self, role: RoleId, account: AccountId) {
+            if !self.has_role(role, account) {
+                return;
+            }
+            self.roles.remove((role, account));
+            self.env().emit_event(RoleRevoked {
+                role,
+                account,
+                sender: self.env().caller(),
+            });
+        }
+    }
+
+    impl Default for SimpleCounter {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Counter for SimpleCounter {
+        #[ink(message)]
+        fn increase_number(&mut self) -> Result<(), CounterError> {
+            self.increase_by(1)
+        }
+
+        #[ink(message)]
+        fn decrease_number(&mut self) -> Result<(), CounterError> {
+            self.decrease_by(1)
+        }
+
+        /// Simply returns the current value of our `bool`.
+        #[ink(message)]
+        fn retrieve_number(&self) -> u128 {
+            self.number
+        }
+    }
+
+    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
+    /// module and test functions are marked with a `#[test]` attribute.
+    /// The below code is technically just normal Rust code.
+    #[cfg(test)]
+    mod tests {
+        /// Imports all the definitions from the outer scope so we can use them here.
+        use super::*;
+        use ink::env::test;
+        use ink::storage::traits::StorageKey;
+
+        /// Clave pública ECDSA comprimida de la clave privada `[0x42; 32]`.
+        const SIGNER_PUBLIC_KEY: [u8; 33] = [
+            0x03, 0x24, 0x65, 0x3e, 0xac, 0x43, 0x44, 0x88, 0x00, 0x2c, 0xc0, 0x6b, 0xbf, 0xb7, 0xf1, 0x0f,
+            0xe1, 0x89, 0x91, 0xe3, 0x5f, 0x9f, 0xe4, 0x30, 0x2d, 0xbe, 0xa6, 0xd2, 0x35, 0x3d, 0xc0, 0xab,
+            0x1c,
+        ];
+
+        /// Firma de `Increase` con nonce 0 y deadline 5_000, con el contrato en la cuenta de `frank`.
+        const INCREASE_SIGNATURE: [u8; 65] = [
+            0x2c, 0x75, 0xa9, 0xae, 0x98, 0xb5, 0x23, 0xae, 0x33, 0x17, 0x6a, 0xad, 0x8a, 0xa1, 0x1f, 0x24,
+            0xdd, 0x18, 0x5b, 0xc8, 0xa2, 0xea, 0x4d, 0x87, 0x0c, 0x93, 0x6f, 0x19, 0x6b, 0x89, 0xb0, 0x96,
+            0x7c, 0xf2, 0xa7, 0x20, 0xa1, 0xdd, 0xef, 0x2c, 0x1e, 0xe7, 0xee, 0x19, 0xa2, 0x39, 0xf2, 0xb7,
+            0xeb, 0x4b, 0xcc, 0xef, 0x95, 0xb7, 0x28, 0xa0, 0xbb, 0xb3, 0x66, 0xe9, 0x27, 0xcc, 0xaf, 0x2f,
+            0x01,
+        ];
+
+        /// Firma de `Decrease` con nonce 1 y deadline 5_000, con el contrato en la cuenta de `frank`.
+        const DECREASE_SIGNATURE: [u8; 65] = [
+            0x53, 0x89, 0x58, 0x9a, 0x0a, 0x10, 0x3e, 0x89, 0x44, 0x71, 0xa1, 0x7a, 0x91, 0x93, 0xa8, 0x65,
+            0x1c, 0x42, 0x9b, 0xc7, 0x94, 0x77, 0xcc, 0x4d, 0x56, 0x66, 0xaa, 0x44, 0x64, 0xc8, 0x7f, 0xff,
+            0x35, 0xcb, 0x32, 0x98, 0x13, 0xc4, 0x70, 0x44, 0x8b, 0xf4, 0x31, 0x26, 0x65, 0x81, 0xed, 0x5f,
+            0xc1, 0x55, 0x82, 0xc7, 0x1a, 0x8c, 0x28, 0x98, 0xed, 0x88, 0xa3, 0xb2, 0xea, 0xb8, 0xcd, 0xeb,
+            0x00,
+        ];
+
+        fn leaf(account: AccountId) -> [u8; 32] {
+            let mut output = [0u8; 32];
+            ink::env::hash_bytes::<Blake2x256>(account.as_ref(), &mut output);
+            output
+        }
+
+        fn node(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+            let (first, second) = if a <= b { (a, b) } else { (b, a) };
+            let mut pair = [0u8; 64];
+            pair[..32].copy_from_slice(&first);
+            pair[32..].copy_from_slice(&second);
+            let mut output = [0u8; 32];
+            ink::env::hash_bytes::<Blake2x256>(&pair, &mut output);
+            output
+        }
+
+        fn signer_account() -> AccountId {
+            let mut output = [0u8; 32];
+            ink::env::hash_bytes::<Blake2x256>(&SIGNER_PUBLIC_KEY, &mut output);
+            AccountId::from(output)
+        }
+
+        /// We test a simple use case of our contract.
+        #[ink::test]
+        fn it_works() {
+            let simple_counter = SimpleCounter::new();
+            assert_eq!(simple_counter.retrieve_number(), 0);
+        }
+
+        #[ink::test]
+        fn owner_can_add_to_whitelist() {
+            // Obtenemos las cuentas por defecto.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Establecemos a `alice` como el owner.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+
+            // El owner (alice) agrega a `bob` a la whitelist.
+            assert_eq!(contract.add_to_white_list(accounts.bob, None), Ok(()));
+
+            // Verificamos que `bob` esté en la whitelist.
+            let is_whitelisted = contract.white_list.get(&accounts.bob).unwrap_or(false);
+            assert_eq!(is_whitelisted, true, "El owner debería poder agregar a alguien a la whitelist");
+        }
+        
+        #[ink::test]
+        fn owner_can_remove_from_whitelist() {
+            // Obtenemos las cuentas por defecto.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Establecemos a `alice` como el owner.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+
+            // El owner agrega a `bob` a la whitelist.
+            assert_eq!(contract.add_to_white_list(accounts.bob, None), Ok(()));
+            // Verificamos que `bob` esté en la whitelist.
+            let is_whitelisted = contract.white_list.get(&accounts.bob).unwrap_or(false);
+            assert_eq!(is_whitelisted, true, "bob debería estar en la whitelist");
+
+            // El owner remueve a `bob` de la whitelist.
+            assert_eq!(contract.remove_from_white_list(accounts.bob), Ok(()));
+            // Verificamos que `bob` ya no esté en la whitelist.
+            let is_whitelisted = contract.white_list.get(&accounts.bob).unwrap_or(false);
+            assert_eq!(is_whitelisted, false, "bob ya no debería estar en la whitelist");
+        }
+
+        #[ink::test]
+        fn only_admin_can_add_to_whitelist() {
+            // Obtenemos cuentas de prueba.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Establecemos a `alice` como el caller, que será el owner.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+
+            // Ahora, cambiamos el caller a `bob`, que no es admin.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            // Como `bob` no es admin, se debe devolver `MissingRole` al intentar agregar a alguien a la whitelist.
+            assert_eq!(contract.add_to_white_list(accounts.eve, None), Err(CounterError::MissingRole));
+        }
+
+        #[ink::test]
+        fn only_admin_can_remove_from_whitelist() {
+            // Obtenemos cuentas de prueba
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Establecemos a `alice` como el owner
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+
+            // Para asegurarnos que hay algo en la whitelist, lo agregamos desde el owner
+            assert_eq!(contract.add_to_white_list(accounts.eve, None), Ok(()));
+
+            // Cambiamos el caller a `bob`, que no es admin
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            // Al intentar remover de la whitelist, se debe devolver `MissingRole` ya que `bob` no es admin
+            assert_eq!(contract.remove_from_white_list(accounts.eve), Err(CounterError::MissingRole));
+        }
+
+        #[ink::test]
+        fn increase_and_decrease_work() {
+            use ink::env::test;
+
+            // Obtenemos las cuentas de prueba, donde `alice` será el owner.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Establecemos a `alice` como caller y, por ende, owner.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+
+            // Para poder llamar a `increase_number` y `decrease_number`,
+            // debemos asegurarnos de que el caller (alice) esté en la whitelist.
+            assert_eq!(contract.add_to_white_list(accounts.alice, None), Ok(()));
+
+            // Verificamos que el número inicial sea 0.
+            assert_eq!(contract.retrieve_number(), 0);
+
+            // Llamamos a increase_number y verificamos que el número aumente a 1.
+            assert_eq!(contract.increase_number(), Ok(()));
+            assert_eq!(contract.retrieve_number(), 1);
+
+            // Llamamos a decrease_number y verificamos que el número regrese a 0.
+            assert_eq!(contract.decrease_number(), Ok(()));
+            assert_eq!(contract.retrieve_number(), 0);
+        }
+
+        #[ink::test]
+        fn increase_only_whitelisted() {
+            use ink::env::test;
+
+            // Obtenemos las cuentas por defecto.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Configuramos al owner (alice) y creamos el contrato.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+
+            // No agregamos a bob a la whitelist.
+            // Cambiamos el caller a bob, que no está en la whitelist.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            // Se espera que al intentar aumentar el contador se devuelva `NotWhitelisted`.
+            assert_eq!(contract.increase_number(), Err(CounterError::NotWhitelisted));
+        }
+
+        #[ink::test]
+        fn decrease_only_whitelisted() {
+            use ink::env::test;
+
+            // Obtenemos las cuentas por defecto.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+
+            // Configuramos al owner (alice) y creamos el contrato.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+
+            // No agregamos a bob a la whitelist.
+            // Cambiamos el caller a bob, que no está en la whitelist.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+
+            // Se espera que al intentar disminuir el contador se devuelva `NotWhitelisted`.
+            assert_eq!(contract.decrease_number(), Err(CounterError::NotWhitelisted));
+        }
+
+        #[ink::test]
+        fn decrease_at_zero_returns_underflow() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.add_to_white_list(accounts.alice, None), Ok(()));
+
+            // El contador empieza en 0, por lo que disminuirlo debe devolver `Underflow`
+            // y dejar el valor sin cambios.
+            assert_eq!(contract.decrease_number(), Err(CounterError::Underflow));
+            assert_eq!(contract.retrieve_number(), 0);
+        }
+
+        #[ink::test]
+        fn increase_at_max_returns_overflow() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.add_to_white_list(accounts.alice, None), Ok(()));
+
+            // Forzamos el contador al máximo: aumentarlo debe devolver `Overflow`.
+            contract.number = u128::MAX;
+            assert_eq!(contract.increase_number(), Err(CounterError::Overflow));
+            assert_eq!(contract.retrieve_number(), u128::MAX);
+        }
+
+        #[ink::test]
+        fn two_step_ownership_transfer_works() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+
+            // `alice` propone a `bob` como nuevo owner, pero sigue siendo owner hasta que `bob` acepte.
+            assert_eq!(contract.transfer_ownership(accounts.bob), Ok(()));
+            assert_eq!(contract.owner(), Some(accounts.alice));
+            assert_eq!(contract.pending_owner(), Some(accounts.bob));
+
+            // `charlie` no puede aceptar una transferencia que no es para él.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.accept_ownership(), Err(CounterError::NotPendingOwner));
+
+            // `bob` acepta y pasa a ser el owner.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.accept_ownership(), Ok(()));
+            assert_eq!(contract.owner(), Some(accounts.bob));
+            assert_eq!(contract.pending_owner(), None);
+            assert_eq!(contract.set_role_admin(INCREMENTER_ROLE, ADMIN_ROLE), Ok(()));
+
+            // `alice` ya no es owner.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.transfer_ownership(accounts.eve), Err(CounterError::NotOwner));
+        }
+
+        #[ink::test]
+        fn owner_can_cancel_ownership_transfer() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+
+            // Sin transferencia en curso no hay nada que cancelar.
+            assert_eq!(contract.cancel_ownership_transfer(), Err(CounterError::NoPendingOwner));
+
+            // `alice` propone a `bob` y luego se arrepiente.
+            assert_eq!(contract.transfer_ownership(accounts.bob), Ok(()));
+            assert_eq!(contract.cancel_ownership_transfer(), Ok(()));
+            assert_eq!(contract.pending_owner(), None);
+
+            // `bob` ya no puede aceptar.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.accept_ownership(), Err(CounterError::NoPendingOwner));
+        }
+
+        #[ink::test]
+        fn renounce_ownership_leaves_contract_without_owner() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+
+            // Solo el owner puede renunciar.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.renounce_ownership(), Err(CounterError::NotOwner));
+
+            // `alice` renuncia: ya nadie puede usar los mensajes reservados al owner.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.renounce_ownership(), Ok(()));
+            assert_eq!(contract.owner(), None);
+            assert_eq!(contract.transfer_ownership(accounts.bob), Err(CounterError::NotOwner));
+        }
+
+        #[ink::test]
+        fn several_admins_can_manage_whitelist() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            assert!(contract.has_role(ADMIN_ROLE, accounts.alice));
+
+            // `alice` nombra admin a `bob`, que ahora puede gestionar la whitelist.
+            assert_eq!(contract.grant_role(ADMIN_ROLE, accounts.bob), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.add_to_white_list(accounts.charlie, None), Ok(()));
+
+            // `bob` renuncia al rol y pierde el permiso.
+            assert_eq!(contract.renounce_role(ADMIN_ROLE), Ok(()));
+            assert!(!contract.has_role(ADMIN_ROLE, accounts.bob));
+            assert_eq!(contract.add_to_white_list(accounts.eve, None), Err(CounterError::MissingRole));
+            assert_eq!(contract.renounce_role(ADMIN_ROLE), Err(CounterError::MissingRole));
+        }
+
+        #[ink::test]
+        fn incrementer_and_decrementer_roles_are_independent() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+
+            // `bob` solo puede incrementar y `charlie` solo puede decrementar.
+            assert_eq!(contract.grant_role(INCREMENTER_ROLE, accounts.bob), Ok(()));
+            assert_eq!(contract.grant_role(DECREMENTER_ROLE, accounts.charlie), Ok(()));
+
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.increase_number(), Ok(()));
+            assert_eq!(contract.decrease_number(), Err(CounterError::NotWhitelisted));
+
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.increase_number(), Err(CounterError::NotWhitelisted));
+            assert_eq!(contract.decrease_number(), Ok(()));
+            assert_eq!(contract.retrieve_number(), 0);
+
+            // Al revocarle el rol, `bob` ya no puede incrementar.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.revoke_role(INCREMENTER_ROLE, accounts.bob), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.increase_number(), Err(CounterError::NotWhitelisted));
+        }
+
+        #[ink::test]
+        fn role_admin_hierarchy_is_respected() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+
+            // Los incrementadores pasan a ser administrados por el rol de decrementadores.
+            assert_eq!(contract.get_role_admin(INCREMENTER_ROLE), ADMIN_ROLE);
+            assert_eq!(contract.set_role_admin(INCREMENTER_ROLE, DECREMENTER_ROLE), Ok(()));
+            assert_eq!(contract.get_role_admin(INCREMENTER_ROLE), DECREMENTER_ROLE);
+            assert_eq!(contract.grant_role(DECREMENTER_ROLE, accounts.bob), Ok(()));
+
+            // `bob` puede otorgar el rol de incrementador, pero no el de admin.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.grant_role(INCREMENTER_ROLE, accounts.charlie), Ok(()));
+            assert!(contract.has_role(INCREMENTER_ROLE, accounts.charlie));
+            assert_eq!(contract.grant_role(ADMIN_ROLE, accounts.charlie), Err(CounterError::MissingRole));
+
+            // Solo el owner puede cambiar la jerarquía.
+            assert_eq!(contract.set_role_admin(ADMIN_ROLE, DECREMENTER_ROLE), Err(CounterError::NotOwner));
+        }
+
+        #[ink::test]
+        fn increase_by_and_decrease_by_work() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.add_to_white_list(accounts.alice, None), Ok(()));
+
+            // Aumentamos de a 10 y disminuimos de a 3.
+            assert_eq!(contract.increase_by(10), Ok(()));
+            assert_eq!(contract.decrease_by(3), Ok(()));
+            assert_eq!(contract.retrieve_number(), 7);
+
+            // No se puede bajar más de lo que hay.
+            assert_eq!(contract.decrease_by(8), Err(CounterError::Underflow));
+            assert_eq!(contract.retrieve_number(), 7);
+        }
+
+        #[ink::test]
+        fn bounds_are_enforced_on_every_change() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.add_to_white_list(accounts.alice, None), Ok(()));
+
+            // Límites inválidos: mínimo mayor que el máximo, o el valor actual (0) fuera de rango.
+            assert_eq!(contract.set_bounds(10, 5), Err(CounterError::InvalidBounds));
+            assert_eq!(contract.set_bounds(1, 5), Err(CounterError::InvalidBounds));
+
+            // Con el rango 0..=5 no podemos pasarnos de 5 ni con `increase_by` ni con `set_number`.
+            assert_eq!(contract.set_bounds(0, 5), Ok(()));
+            assert_eq!(contract.bounds(), (0, 5));
+            assert_eq!(contract.increase_by(5), Ok(()));
+            assert_eq!(contract.increase_number(), Err(CounterError::OutOfBounds));
+            assert_eq!(contract.set_number(6), Err(CounterError::OutOfBounds));
+            assert_eq!(contract.retrieve_number(), 5);
+
+            // Solo el owner configura los límites.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.set_bounds(0, 100), Err(CounterError::NotOwner));
+        }
+
+        #[ink::test]
+        fn admin_can_set_number() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            let events_before = test::recorded_events().count();
+
+            // El admin fija el valor en 42 y luego lo baja a 40; cada cambio emite un `NewValue`.
+            assert_eq!(contract.set_number(42), Ok(()));
+            assert_eq!(contract.set_number(40), Ok(()));
+            assert_eq!(contract.retrieve_number(), 40);
+            assert_eq!(test::recorded_events().count(), events_before + 2);
+
+
+            // Quien no es admin no puede fijar el valor.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.set_number(0), Err(CounterError::MissingRole));
+        }
+
+        #[ink::test]
+        fn batch_whitelist_management_works() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            let events_before = test::recorded_events().count();
+
+            // Agregamos a tres cuentas de una vez; `bob` repetido no genera un segundo evento.
+            let team = vec![accounts.bob, accounts.charlie, accounts.django, accounts.bob];
+            assert_eq!(contract.add_many_to_white_list(team), Ok(()));
+            assert_eq!(test::recorded_events().count(), events_before + 3);
+            assert_eq!(contract.white_list.get(accounts.charlie), Some(true));
+
+            // Al removerlos, la entrada del `Mapping` se borra en lugar de guardar `false`.
+            assert_eq!(contract.remove_many_from_white_list(vec![accounts.bob, accounts.charlie]), Ok(()));
+            assert_eq!(test::recorded_events().count(), events_before + 5);
+            assert_eq!(contract.white_list.get(accounts.bob), None);
+            assert_eq!(contract.white_list.get(accounts.charlie), None);
+            assert_eq!(contract.white_list.get(accounts.django), Some(true));
+        }
+
+        #[ink::test]
+        fn batch_whitelist_rejects_large_batches() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+
+            // Un lote de más de `MAX_BATCH_SIZE` cuentas se rechaza completo.
+            let batch = vec![accounts.bob; MAX_BATCH_SIZE as usize + 1];
+            assert_eq!(contract.add_many_to_white_list(batch.clone()), Err(CounterError::BatchTooLarge));
+            assert_eq!(contract.remove_many_from_white_list(batch), Err(CounterError::BatchTooLarge));
+            assert_eq!(contract.white_list.get(accounts.bob), None);
+
+            // Quien no es admin no puede usar los mensajes por lote.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.add_many_to_white_list(vec![accounts.eve]), Err(CounterError::MissingRole));
+        }
+
+        #[ink::test]
+        fn whitelist_can_be_enumerated() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.whitelist_len(), 0);
+            assert_eq!(contract.whitelist_page(0, 10), Vec::new());
+
+            // Agregamos a cuatro cuentas y las leemos en páginas de a dos.
+            let team = vec![accounts.bob, accounts.charlie, accounts.django, accounts.eve];
+            assert_eq!(contract.add_many_to_white_list(team), Ok(()));
+            assert_eq!(contract.whitelist_len(), 4);
+            assert!(contract.is_whitelisted(accounts.django));
+            assert_eq!(contract.whitelist_page(0, 2), vec![accounts.bob, accounts.charlie]);
+            assert_eq!(contract.whitelist_page(2, 2), vec![accounts.django, accounts.eve]);
+            assert_eq!(contract.whitelist_page(4, 2), Vec::new());
+
+            // Al remover a `bob`, la última cuenta ocupa su lugar y el conjunto sigue completo.
+            assert_eq!(contract.remove_from_white_list(accounts.bob), Ok(()));
+            assert!(!contract.is_whitelisted(accounts.bob));
+            assert_eq!(contract.whitelist_len(), 3);
+            assert_eq!(
+                contract.whitelist_page(0, 10),
+                vec![accounts.eve, accounts.charlie, accounts.django]
+            );
+
+            // Remover la última cuenta también mantiene la lista consistente.
+            assert_eq!(contract.remove_from_white_list(accounts.django), Ok(()));
+            assert_eq!(contract.whitelist_page(0, 10), vec![accounts.eve, accounts.charlie]);
+        }
+
+        #[ink::test]
+        fn whitelist_page_is_capped() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+
+            // Llenamos la whitelist con más cuentas que `MAX_PAGE_SIZE`.
+            for i in 0..=MAX_PAGE_SIZE {
+                let mut bytes = [0u8; 32];
+                bytes[..4].copy_from_slice(&i.to_le_bytes());
+                assert_eq!(contract.add_to_white_list(AccountId::from(bytes), None), Ok(()));
+            }
+
+            // Aunque pidamos más, la página no supera `MAX_PAGE_SIZE`.
+            assert_eq!(contract.whitelist_len(), MAX_PAGE_SIZE + 1);
+            assert_eq!(contract.whitelist_page(0, u32::MAX).len(), MAX_PAGE_SIZE as usize);
+            assert_eq!(contract.whitelist_page(MAX_PAGE_SIZE, u32::MAX).len(), 1);
+        }
+
+        #[ink::test]
+        fn whitelist_entries_expire() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            let mut contract = SimpleCounter::new();
+
+            // No se puede dar acceso con un vencimiento que ya pasó.
+            assert_eq!(contract.add_to_white_list(accounts.bob, Some(1_000)), Err(CounterError::InvalidExpiry));
+
+            // `bob` es un contratista con acceso hasta el instante 2_000.
+            assert_eq!(contract.add_to_white_list(accounts.bob, Some(2_000)), Ok(()));
+            assert_eq!(contract.access_expires_at(accounts.bob), Some(2_000));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.increase_number(), Ok(()));
+
+            // Pasado el vencimiento, el acceso caduca solo.
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(2_000);
+            assert!(!contract.is_whitelisted(accounts.bob));
+            assert_eq!(contract.increase_number(), Err(CounterError::AccessExpired));
+            assert_eq!(contract.retrieve_number(), 1);
+        }
+
+        #[ink::test]
+        fn admin_can_extend_access() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            let mut contract = SimpleCounter::new();
+
+            // Solo se puede extender el acceso de quien está en la whitelist.
+            assert_eq!(contract.extend_access(accounts.bob, Some(5_000)), Err(CounterError::NotWhitelisted));
+
+            // Extendemos el acceso de `bob` y luego lo hacemos permanente.
+            assert_eq!(contract.add_to_white_list(accounts.bob, Some(2_000)), Ok(()));
+            assert_eq!(contract.extend_access(accounts.bob, Some(5_000)), Ok(()));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(3_000);
+            assert!(contract.is_whitelisted(accounts.bob));
+            assert_eq!(contract.extend_access(accounts.bob, None), Ok(()));
+            assert_eq!(contract.access_expires_at(accounts.bob), None);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(10_000);
+            assert!(contract.is_whitelisted(accounts.bob));
+
+            // Al remover a `bob` también se borra su vencimiento.
+            assert_eq!(contract.extend_access(accounts.bob, Some(20_000)), Ok(()));
+            assert_eq!(contract.remove_from_white_list(accounts.bob), Ok(()));
+            assert_eq!(contract.access_expires_at(accounts.bob), None);
+        }
+
+        #[ink::test]
+        fn adding_a_member_again_replaces_its_expiry() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.add_to_white_list(accounts.bob, Some(2_000)), Ok(()));
+            assert_eq!(contract.add_to_white_list(accounts.charlie, Some(2_000)), Ok(()));
+
+            // Agregar de nuevo a `bob` cambia su vencimiento y emite `AccessExtended`.
+            let events_before = test::recorded_events().count();
+            assert_eq!(contract.add_to_white_list(accounts.bob, Some(5_000)), Ok(()));
+            assert_eq!(contract.access_expires_at(accounts.bob), Some(5_000));
+            assert_eq!(test::recorded_events().count(), events_before + 1);
+
+            // Repetir el mismo vencimiento no emite nada.
+            assert_eq!(contract.add_to_white_list(accounts.bob, Some(5_000)), Ok(()));
+            assert_eq!(test::recorded_events().count(), events_before + 1);
+
+            // El alta por lotes da acceso permanente también a quienes ya estaban.
+            assert_eq!(contract.add_many_to_white_list(vec![accounts.bob, accounts.charlie, accounts.django]), Ok(()));
+            assert_eq!(contract.access_expires_at(accounts.bob), None);
+            assert_eq!(contract.access_expires_at(accounts.charlie), None);
+            assert_eq!(test::recorded_events().count(), events_before + 4);
+            assert_eq!(contract.whitelist_len(), 3);
+        }
+
+        #[ink::test]
+        fn cooldown_limits_each_account() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.add_many_to_white_list(vec![accounts.bob, accounts.charlie]), Ok(()));
+
+            // Configuramos una espera de 500 ms entre llamadas.
+            assert_eq!(contract.set_rate_limit(500, None), Ok(()));
+            assert_eq!(contract.next_allowed_call(accounts.bob), 0);
+
+            // `bob` incrementa y tiene que esperar; `charlie` no se ve afectado.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.increase_number(), Ok(()));
+            assert_eq!(contract.next_allowed_call(accounts.bob), 1_500);
+            assert_eq!(contract.decrease_number(), Err(CounterError::RateLimited));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.increase_number(), Ok(()));
+
+            // Pasada la espera, `bob` puede volver a llamar.
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_500);
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.decrease_number(), Ok(()));
+            assert_eq!(contract.retrieve_number(), 1);
         }
 
+        #[ink::test]
+        fn quota_limits_calls_per_window() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.add_to_white_list(accounts.bob, None), Ok(()));
 
-        #[ink(message)]
-        pub fn add_to_white_list(&mut self, address: AccountId) {
-            self.ensure_owner();
-            self.white_list.insert(address, &true);
-        }
+            // Una cuota vacía no es válida; configuramos 2 llamadas cada 1_000 ms.
+            let empty = Quota { max_calls: 0, window: 1_000 };
+            assert_eq!(contract.set_rate_limit(0, Some(empty)), Err(CounterError::InvalidRateLimit));
+            let quota = Quota { max_calls: 2, window: 1_000 };
+            assert_eq!(contract.set_rate_limit(0, Some(quota)), Ok(()));
+            assert_eq!(contract.rate_limit(), (0, Some(quota)));
 
-        #[ink(message)]
-        pub fn remove_from_white_list(&mut self, address: AccountId) {
-            self.ensure_owner();
-            self.white_list.insert(address, &false);
-        }
+            // `bob` agota la cuota y la tercera llamada se rechaza hasta que cierre la ventana.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.increase_number(), Ok(()));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_200);
+            assert_eq!(contract.increase_number(), Ok(()));
+            assert_eq!(contract.increase_number(), Err(CounterError::RateLimited));
+            assert_eq!(contract.next_allowed_call(accounts.bob), 2_000);
 
-        #[ink(message)]
-        pub fn increase_number(&mut self) {
-            self.ensure_white_list();
-            self.number = self
-                .number
-                .checked_add(1)
-                .expect("Overflow en increase_number");
-            self.env().emit_event(NewValue {
-                sender: self.env().caller(),
-                new_number: self.number,
-            });
-        }
+            // En la ventana siguiente vuelve a tener cuota.
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(2_000);
+            assert_eq!(contract.increase_number(), Ok(()));
+            assert_eq!(contract.retrieve_number(), 3);
 
-        #[ink(message)]
-        pub fn decrease_number(&mut self) {
-            self.ensure_white_list();
-            self.number = self
-                .number
-                .checked_sub(1)
-                .expect("Underflow en decrease_number");
-            self.env().emit_event(NewValue {
-                sender: self.env().caller(),
-                new_number: self.number,
-            });
+            // Solo el owner configura el límite.
+            assert_eq!(contract.set_rate_limit(0, None), Err(CounterError::NotOwner));
         }
 
-        /// Simply returns the current value of our `bool`.
-        #[ink(message)]
-        pub fn retrieve_number(&self) -> u128 {
-            self.number
+        #[ink::test]
+        fn value_at_returns_historical_values() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.add_to_white_list(accounts.alice, None), Ok(()));
+
+            // Bloque 0: se despliega el contrato en 0 y se incrementa dos veces.
+            assert_eq!(contract.increase_number(), Ok(()));
+            assert_eq!(contract.increase_number(), Ok(()));
+            // Bloques 1 y 2 sin cambios; en el bloque 3 se fija el valor en 10.
+            test::advance_block::<ink::env::DefaultEnvironment>();
+            test::advance_block::<ink::env::DefaultEnvironment>();
+            test::advance_block::<ink::env::DefaultEnvironment>();
+            assert_eq!(contract.set_number(10), Ok(()));
+            // Bloque 4: se decrementa.
+            test::advance_block::<ink::env::DefaultEnvironment>();
+            assert_eq!(contract.decrease_number(), Ok(()));
+
+            // Los cambios de un mismo bloque comparten un único checkpoint.
+            assert_eq!(contract.history_len(), 3);
+            assert_eq!(contract.value_at(0), Some(2));
+            assert_eq!(contract.value_at(2), Some(2));
+            assert_eq!(contract.value_at(3), Some(10));
+            assert_eq!(contract.value_at(4), Some(9));
+            assert_eq!(contract.value_at(100), Some(9));
         }
 
+        #[ink::test]
+        fn history_is_paginated() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::advance_block::<ink::env::DefaultEnvironment>();
+            let mut contract = SimpleCounter::new();
+
+            // Antes del despliegue no hay valor.
+            assert_eq!(contract.value_at(0), None);
+
+            // Un cambio por bloque durante tres bloques.
+            for value in 1..=3 {
+                test::advance_block::<ink::env::DefaultEnvironment>();
+                assert_eq!(contract.set_number(value), Ok(()));
+            }
 
-        fn ensure_owner(&self){
-            assert_eq!(self.env().caller(), self.owner,
-                "Caller is not owner");
+            assert_eq!(
+                contract.history(1, 2),
+                vec![Checkpoint { block: 2, value: 1 }, Checkpoint { block: 3, value: 2 }]
+            );
+            assert_eq!(contract.history(0, u32::MAX).len(), 4);
+            assert_eq!(contract.history(4, 10), Vec::new());
         }
 
-        fn ensure_white_list(&self){
-            assert!(&self.white_list.get(&self.env().caller()).unwrap_or(false),
-                "Caller is not in the white list");
+        #[ink::test]
+        fn named_counters_are_independent() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.add_to_white_list(accounts.bob, None), Ok(()));
+
+            // Creamos dos contadores abiertos a la whitelist general.
+            let visits = contract.create_counter(String::from("visits"), accounts.alice, false).unwrap();
+            let errors = contract.create_counter(String::from("errors"), accounts.alice, false).unwrap();
+            assert_ne!(visits, errors);
+            assert_ne!(visits, DEFAULT_COUNTER);
+
+            // `bob` los modifica por separado sin tocar el contador original.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.increase(visits), Ok(()));
+            assert_eq!(contract.increase(visits), Ok(()));
+            assert_eq!(contract.increase(errors), Ok(()));
+            assert_eq!(contract.decrease(errors), Ok(()));
+            assert_eq!(contract.decrease(errors), Err(CounterError::Underflow));
+            assert_eq!(contract.increase(DEFAULT_COUNTER), Ok(()));
+            assert_eq!(contract.retrieve(visits), Some(2));
+            assert_eq!(contract.retrieve(errors), Some(0));
+            assert_eq!(contract.retrieve(DEFAULT_COUNTER), Some(1));
+            assert_eq!(contract.retrieve_number(), 1);
+
+            // Un contador inexistente devuelve `UnknownCounter`.
+            assert_eq!(contract.retrieve(99), None);
+            assert_eq!(contract.increase(99), Err(CounterError::UnknownCounter));
+
+            // Solo un admin puede crear contadores.
+            assert_eq!(
+                contract.create_counter(String::from("spam"), accounts.bob, false),
+                Err(CounterError::MissingRole)
+            );
         }
-    }
 
-    /// Unit tests in Rust are normally defined within such a `#[cfg(test)]`
-    /// module and test functions are marked with a `#[test]` attribute.
-    /// The below code is technically just normal Rust code.
-    #[cfg(test)]
-    mod tests {
-        /// Imports all the definitions from the outer scope so we can use them here.
-        use super::*;
-        use ink::env::test;
+        #[ink::test]
+        fn restricted_counter_uses_its_own_whitelist() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.add_to_white_list(accounts.bob, None), Ok(()));
+
+            // `charlie` administra un contador restringido.
+            let deploys = contract.create_counter(String::from("deploys"), accounts.charlie, true).unwrap();
+            assert_eq!(contract.counter_info(deploys).map(|info| info.admin), Some(accounts.charlie));
+
+            // `bob` está en la whitelist general, pero no en la del contador.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.increase(deploys), Err(CounterError::NotWhitelisted));
+            assert_eq!(contract.set_counter_access(deploys, accounts.bob, true), Err(CounterError::NotCounterAdmin));
+
+            // `charlie` habilita a `bob`, y luego le cede la administración a `django`.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.increase(deploys), Ok(()));
+            assert_eq!(contract.set_counter_access(deploys, accounts.bob, true), Ok(()));
+            assert_eq!(contract.set_counter_admin(deploys, accounts.django), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.increase(deploys), Ok(()));
+            assert_eq!(contract.retrieve(deploys), Some(2));
+
+            // `django` le quita el acceso a `bob`.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.set_counter_access(deploys, accounts.bob, false), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.decrease(deploys), Err(CounterError::NotWhitelisted));
+        }
 
-        /// We test a simple use case of our contract.
         #[ink::test]
-        fn it_works() {
-            let simple_counter = SimpleCounter::new();
-            assert_eq!(simple_counter.retrieve_number(), 0);
+        fn pause_freezes_counter_changes() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.add_to_white_list(accounts.alice, None), Ok(()));
+            let visits = contract.create_counter(String::from("visits"), accounts.alice, false).unwrap();
+            assert_eq!(contract.increase_number(), Ok(()));
+
+            // Al pausar, ningún mensaje que cambie el estado funciona, pero se puede seguir leyendo.
+            assert_eq!(contract.unpause(), Err(CounterError::NotPaused));
+            assert_eq!(contract.pause(), Ok(()));
+            assert!(contract.is_paused());
+            assert_eq!(contract.pause(), Err(CounterError::Paused));
+            assert_eq!(contract.increase_number(), Err(CounterError::Paused));
+            assert_eq!(contract.decrease_by(1), Err(CounterError::Paused));
+            assert_eq!(contract.set_number(5), Err(CounterError::Paused));
+            assert_eq!(contract.increase(visits), Err(CounterError::Paused));
+            assert_eq!(contract.retrieve_number(), 1);
+
+            // La administración también queda bloqueada durante la pausa.
+            assert_eq!(contract.remove_from_white_list(accounts.alice), Err(CounterError::Paused));
+            assert_eq!(contract.grant_role(INCREMENTER_ROLE, accounts.bob), Err(CounterError::Paused));
+            assert_eq!(contract.transfer_ownership(accounts.bob), Err(CounterError::Paused));
+            assert_eq!(contract.create_counter(String::from("clicks"), accounts.alice, false), Err(CounterError::Paused));
+            assert_eq!(contract.set_fees(10, None), Err(CounterError::Paused));
+            assert_eq!(contract.add_milestone(10), Err(CounterError::Paused));
+            assert_eq!(contract.propose_reset(0), Err(CounterError::Paused));
+            assert!(contract.is_whitelisted(accounts.alice));
+            assert_eq!(contract.migrate(), Err(CounterError::AlreadyMigrated));
+
+            // Al reanudar, los cambios vuelven a funcionar.
+            assert_eq!(contract.unpause(), Ok(()));
+            assert!(!contract.is_paused());
+            assert_eq!(contract.set_number(5), Ok(()));
         }
 
         #[ink::test]
-        fn owner_can_add_to_whitelist() {
-            // Obtenemos las cuentas por defecto.
+        fn only_owner_or_pauser_can_pause() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
             let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
 
-            // Establecemos a `alice` como el owner.
+            // `bob` no puede pausar hasta que recibe el rol de pauser.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.pause(), Err(CounterError::MissingRole));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.grant_role(PAUSER_ROLE, accounts.bob), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.pause(), Ok(()));
+            assert_eq!(contract.unpause(), Ok(()));
+        }
+
+        #[ink::test]
+        fn packed_root_is_frozen_and_new_cells_default() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
             let mut contract = SimpleCounter::new();
+            assert_eq!(contract.storage_version(), STORAGE_VERSION);
+            assert_eq!(contract.add_to_white_list(accounts.bob, None), Ok(()));
+            assert_eq!(contract.add_milestone(5), Ok(()));
+            assert_eq!(contract.set_fees(10, None), Ok(()));
 
-            // El owner (alice) agrega a `bob` a la whitelist.
-            contract.add_to_white_list(accounts.bob);
+            // La raíz empaquetada de la versión 1 no puede cambiar: en orden, number, owner,
+            // pending_owner, white_list_len, min_value, max_value, cooldown, quota,
+            // checkpoints_len, next_counter_id y paused.
+            type V1Root = (u128, Option<AccountId>, Option<AccountId>, u32, u128, u128, Timestamp, Option<Quota>, u32, CounterId, bool);
+            let root: V1Root = (7, Some(accounts.alice), None, 1, 0, 100, 0, None, 1, DEFAULT_COUNTER + 1, false);
+            ink::env::set_contract_storage(&<SimpleCounter as StorageKey>::KEY, &root);
+            let stored: V1Root = ink::env::get_contract_storage(&<SimpleCounter as StorageKey>::KEY)
+                .expect("the root has to decode as the v1 tuple")
+                .expect("the root was written");
+            assert_eq!(stored, root);
 
-            // Verificamos que `bob` esté en la whitelist.
-            let is_whitelisted = contract.white_list.get(&accounts.bob).unwrap_or(false);
-            assert_eq!(is_whitelisted, true, "El owner debería poder agregar a alguien a la whitelist");
+            // Borramos celdas `Lazy`, como si una versión anterior no las hubiera escrito.
+            for key in [
+                contract.storage_version.key(),
+                contract.milestones.key(),
+                contract.fee.key(),
+            ] {
+                ink::env::clear_contract_storage(&key);
+            }
+            let mut contract: SimpleCounter = ink::env::get_contract_storage(&<SimpleCounter as StorageKey>::KEY)
+                .expect("the v1 root has to decode")
+                .expect("the v1 root was written");
+
+            // El estado de la raíz se conserva y las celdas ausentes toman su valor por defecto.
+            assert_eq!(contract.storage_version(), 1);
+            assert_eq!(contract.retrieve_number(), 7);
+            assert_eq!(contract.owner(), Some(accounts.alice));
+            assert!(contract.is_whitelisted(accounts.bob));
+            assert_eq!(contract.bounds(), (0, 100));
+            assert_eq!(contract.milestones(), Vec::<u128>::new());
+            assert_eq!(contract.fees(), (0, None));
+            assert_eq!(contract.reset_policy(), (DEFAULT_RESET_THRESHOLD, DEFAULT_RESET_VALIDITY));
+
+            // La versión 1 no tiene pasos de migración pendientes, y solo el owner puede intentarlo.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.migrate(), Err(CounterError::NotOwner));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.migrate(), Err(CounterError::AlreadyMigrated));
         }
-        
+
         #[ink::test]
-        fn owner_can_remove_from_whitelist() {
-            // Obtenemos las cuentas por defecto.
+        fn only_owner_can_set_code() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner.
             let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
 
-            // Establecemos a `alice` como el owner.
+            // El entorno off-chain no soporta `set_code_hash`, así que solo probamos el permiso.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.set_code(Hash::from([0x01; 32])), Err(CounterError::NotOwner));
+        }
+
+        #[ink::test]
+        fn relayer_can_submit_signed_changes() {
+            // Creamos el contrato en la cuenta de `frank` con `alice` como owner, y habilitamos al firmante.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
             let mut contract = SimpleCounter::new();
+            let signer = signer_account();
+            assert_eq!(contract.add_to_white_list(signer, None), Ok(()));
 
-            // El owner agrega a `bob` a la whitelist.
-            contract.add_to_white_list(accounts.bob);
-            // Verificamos que `bob` esté en la whitelist.
-            let is_whitelisted = contract.white_list.get(&accounts.bob).unwrap_or(false);
-            assert_eq!(is_whitelisted, true, "bob debería estar en la whitelist");
+            // `bob` actúa de relayer y envía la autorización firmada.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.increase_with_signature(signer, 0, 5_000, INCREASE_SIGNATURE), Ok(()));
+            assert_eq!(contract.retrieve_number(), 1);
+            assert_eq!(contract.nonce_of(signer), 1);
 
-            // El owner remueve a `bob` de la whitelist.
-            contract.remove_from_white_list(accounts.bob);
-            // Verificamos que `bob` ya no esté en la whitelist.
-            let is_whitelisted = contract.white_list.get(&accounts.bob).unwrap_or(false);
-            assert_eq!(is_whitelisted, false, "bob ya no debería estar en la whitelist");
+            // La misma autorización no puede reutilizarse.
+            assert_eq!(
+                contract.increase_with_signature(signer, 0, 5_000, INCREASE_SIGNATURE),
+                Err(CounterError::InvalidNonce)
+            );
+
+            // Una firma de `Increase` no sirve para `Decrease`.
+            assert_eq!(
+                contract.decrease_with_signature(signer, 1, 5_000, INCREASE_SIGNATURE),
+                Err(CounterError::InvalidSignature)
+            );
+            assert_eq!(contract.decrease_with_signature(signer, 1, 5_000, DECREASE_SIGNATURE), Ok(()));
+            assert_eq!(contract.retrieve_number(), 0);
+            assert_eq!(contract.nonce_of(signer), 2);
         }
 
         #[ink::test]
-        #[should_panic(expected = "Caller is not owner")]
-        fn only_owner_can_add_to_whitelist() {
-            // Obtenemos cuentas de prueba.
+        fn signed_changes_are_validated() {
+            // Creamos el contrato en la cuenta de `frank` con `alice` como owner, sin habilitar al firmante.
             let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
-
-            // Establecemos a `alice` como el caller, que será el owner.
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
             let mut contract = SimpleCounter::new();
+            let signer = signer_account();
+
+            // Una firma válida no alcanza si el firmante no está en la whitelist.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.increase_with_signature(signer, 0, 5_000, INCREASE_SIGNATURE),
+                Err(CounterError::NotWhitelisted)
+            );
 
-            // Ahora, cambiamos el caller a `bob`, que no es el owner.
+            // Una firma a nombre de otra cuenta se rechaza.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.add_to_white_list(signer, None), Ok(()));
+            assert_eq!(contract.add_to_white_list(accounts.charlie, None), Ok(()));
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.increase_with_signature(accounts.charlie, 0, 5_000, INCREASE_SIGNATURE),
+                Err(CounterError::InvalidSignature)
+            );
+            assert_eq!(
+                contract.increase_with_signature(signer, 0, 5_000, [0u8; 65]),
+                Err(CounterError::InvalidSignature)
+            );
 
-            // Como `bob` no es owner, se debe generar un panic al intentar agregar a alguien a la whitelist.
-            contract.add_to_white_list(accounts.eve);
+            // Pasado el deadline la autorización ya no vale.
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(5_001);
+            assert_eq!(
+                contract.increase_with_signature(signer, 0, 5_000, INCREASE_SIGNATURE),
+                Err(CounterError::SignatureExpired)
+            );
+            assert_eq!(contract.retrieve_number(), 0);
+            assert_eq!(contract.nonce_of(signer), 0);
         }
 
         #[ink::test]
-        #[should_panic(expected = "Caller is not owner")]
-        fn only_owner_can_remove_from_whitelist() {
-            // Obtenemos cuentas de prueba
+        fn merkle_proof_grants_access() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner.
             let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
 
-            // Establecemos a `alice` como el owner
+            // Árbol con `bob`, `charlie`, `django` y `eve` como hojas.
+            let bob_charlie = node(leaf(accounts.bob), leaf(accounts.charlie));
+            let django_eve = node(leaf(accounts.django), leaf(accounts.eve));
+            let root = node(bob_charlie, django_eve);
+
+            // Sin raíz publicada no se puede reclamar acceso.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.claim_access(vec![leaf(accounts.bob), django_eve]), Err(CounterError::NoMerkleRoot));
+
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.set_merkle_root(Some(root)), Ok(()));
+            assert_eq!(contract.merkle_root(), Some(root));
+
+            // `charlie` prueba su pertenencia y puede incrementar.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.increase_number(), Err(CounterError::NotWhitelisted));
+            assert_eq!(contract.claim_access(vec![leaf(accounts.bob), django_eve]), Ok(()));
+            assert!(contract.is_whitelisted(accounts.charlie));
+            assert_eq!(contract.increase_number(), Ok(()));
+
+            // `frank` no está en el árbol: cualquier prueba falla.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.frank);
+            assert_eq!(contract.claim_access(vec![leaf(accounts.bob), django_eve]), Err(CounterError::InvalidProof));
+
+            // Al publicar una nueva raíz, el acceso reclamado caduca.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.set_merkle_root(Some(django_eve)), Ok(()));
+            assert!(!contract.is_whitelisted(accounts.charlie));
+
+            // Solo el owner publica la raíz.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.set_merkle_root(Some(root)), Err(CounterError::NotOwner));
+        }
+
+        #[ink::test]
+        fn explicit_whitelist_overrides_merkle_claim() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
             let mut contract = SimpleCounter::new();
 
-            // Para asegurarnos que hay algo en la whitelist, lo agregamos desde el owner
-            contract.add_to_white_list(accounts.eve);
+            // `bob` reclama acceso con un árbol de una sola hoja (prueba vacía).
+            assert_eq!(contract.set_merkle_root(Some(leaf(accounts.bob))), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.claim_access(Vec::new()), Ok(()));
 
-            // Cambiamos el caller a `bob`, que no es owner
+            // Una entrada explícita con vencimiento tiene prioridad sobre el reclamo.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.add_to_white_list(accounts.bob, Some(2_000)), Ok(()));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(2_000);
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.increase_number(), Err(CounterError::AccessExpired));
 
-            // Al intentar remover de la whitelist, se debe generar un panic ya que `bob` no es owner
-            contract.remove_from_white_list(accounts.eve);
+            // Al removerla, vuelve a valer el reclamo Merkle.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.remove_from_white_list(accounts.bob), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.increase_number(), Ok(()));
         }
 
         #[ink::test]
-        fn increase_and_decrease_work() {
-            use ink::env::test;
+        fn milestones_are_reported_in_both_directions() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner y admin.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
 
-            // Obtenemos las cuentas de prueba, donde `alice` será el owner.
+            // Registramos los hitos 10, 5 y 20; se guardan ordenados y sin repetidos.
+            assert_eq!(contract.add_milestone(10), Ok(()));
+            assert_eq!(contract.add_milestone(5), Ok(()));
+            assert_eq!(contract.add_milestone(20), Ok(()));
+            assert_eq!(contract.add_milestone(10), Err(CounterError::MilestoneExists));
+            assert_eq!(contract.milestones(), vec![5, 10, 20]);
+
+            // Subir de 0 a 12 cruza 5 y 10: un `NewValue` más dos `MilestoneReached`.
+            let events_before = test::recorded_events().count();
+            assert_eq!(contract.set_number(12), Ok(()));
+            assert_eq!(test::recorded_events().count(), events_before + 3);
+
+            // Moverse sin cruzar hitos solo emite `NewValue`.
+            assert_eq!(contract.set_number(15), Ok(()));
+            assert_eq!(test::recorded_events().count(), events_before + 4);
+
+            // Bajar de 15 a 9 cruza el 10 hacia abajo.
+            assert_eq!(contract.set_number(9), Ok(()));
+            assert_eq!(test::recorded_events().count(), events_before + 6);
+
+            // Un hito removido deja de reportarse.
+            assert_eq!(contract.remove_milestone(10), Ok(()));
+            assert_eq!(contract.remove_milestone(10), Err(CounterError::UnknownMilestone));
+            assert_eq!(contract.set_number(11), Ok(()));
+            assert_eq!(test::recorded_events().count(), events_before + 7);
+        }
+
+        #[ink::test]
+        fn milestones_are_owner_managed_and_bounded() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner.
             let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
 
-            // Establecemos a `alice` como caller y, por ende, owner.
+            // Se pueden registrar hasta `MAX_MILESTONES` hitos.
+            for milestone in 1..=MAX_MILESTONES as u128 {
+                assert_eq!(contract.add_milestone(milestone), Ok(()));
+            }
+            assert_eq!(contract.add_milestone(1_000), Err(CounterError::TooManyMilestones));
+
+            // El owner elige el suscriptor.
+            assert_eq!(contract.set_subscriber(Some(accounts.django)), Ok(()));
+            assert_eq!(contract.subscriber(), Some(accounts.django));
+            assert_eq!(contract.set_subscriber(None), Ok(()));
+
+            // Nadie más puede configurar hitos ni suscriptor.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.add_milestone(1_000), Err(CounterError::NotOwner));
+            assert_eq!(contract.remove_milestone(1), Err(CounterError::NotOwner));
+            assert_eq!(contract.set_subscriber(Some(accounts.bob)), Err(CounterError::NotOwner));
+        }
+
+        #[ink::test]
+        fn fees_are_charged_and_withdrawn() {
+            // Obtenemos las cuentas por defecto y creamos el contrato con `alice` como owner.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
             let mut contract = SimpleCounter::new();
+            assert_eq!(contract.add_to_white_list(accounts.bob, None), Ok(()));
 
-            // Para poder llamar a `increase_number` y `decrease_number`,
-            // debemos asegurarnos de que el caller (alice) esté en la whitelist.
-            contract.add_to_white_list(accounts.alice);
+            // La tarifa pública tiene que ser mayor que la normal.
+            assert_eq!(contract.set_fees(10, Some(10)), Err(CounterError::InvalidFees));
+            assert_eq!(contract.set_fees(10, Some(50)), Ok(()));
+            assert_eq!(contract.fees(), (10, Some(50)));
 
-            // Verificamos que el número inicial sea 0.
-            assert_eq!(contract.retrieve_number(), 0);
+            // `bob` está en la whitelist: sin pagar la tarifa falla, pagándola incrementa.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(5);
+            assert_eq!(contract.increase_number(), Err(CounterError::FeeTooLow));
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(10);
+            assert_eq!(contract.increase_number(), Ok(()));
 
-            // Llamamos a increase_number y verificamos que el número aumente a 1.
-            contract.increase_number();
-            assert_eq!(contract.retrieve_number(), 1);
+            // `charlie` no está en la whitelist, pero puede incrementar pagando la tarifa pública.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.increase_number(), Err(CounterError::FeeTooLow));
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(50);
+            assert_eq!(contract.increase_number(), Ok(()));
+            // La tarifa pública no habilita a decrementar.
+            assert_eq!(contract.decrease_number(), Err(CounterError::NotWhitelisted));
+            assert_eq!(contract.retrieve_number(), 2);
+            assert_eq!(contract.collected_fees(), 60);
 
-            // Llamamos a decrease_number y verificamos que el número regrese a 0.
-            contract.decrease_number();
+            // Solo el owner retira, y no más de lo recaudado.
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            assert_eq!(contract.withdraw_fees(accounts.charlie, 60), Err(CounterError::NotOwner));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.withdraw_fees(accounts.eve, 61), Err(CounterError::InsufficientFees));
+
+            let contract_id = ink::env::account_id::<ink::env::DefaultEnvironment>();
+            test::set_account_balance::<ink::env::DefaultEnvironment>(contract_id, 60);
+            test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.eve, 0);
+            assert_eq!(contract.withdraw_fees(accounts.eve, 40), Ok(()));
+            assert_eq!(contract.collected_fees(), 20);
+            assert_eq!(test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.eve), Ok(40));
+        }
+
+        #[ink::test]
+        fn fees_apply_to_every_path() {
+            // Creamos el contrato en la cuenta de `frank`, habilitamos al firmante y a `bob`, y fijamos una tarifa.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_callee::<ink::env::DefaultEnvironment>(accounts.frank);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            let mut contract = SimpleCounter::new();
+            let signer = signer_account();
+            assert_eq!(contract.add_to_white_list(signer, None), Ok(()));
+            assert_eq!(contract.add_to_white_list(accounts.bob, None), Ok(()));
+            assert_eq!(contract.set_fees(10, None), Ok(()));
+
+            // `increase`/`decrease` sobre el contador por defecto cobran la tarifa como `increase_number`.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.increase(DEFAULT_COUNTER), Err(CounterError::FeeTooLow));
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(10);
+            assert_eq!(contract.increase(DEFAULT_COUNTER), Ok(()));
+            assert_eq!(contract.decrease(DEFAULT_COUNTER), Ok(()));
+            assert_eq!(contract.collected_fees(), 20);
+
+            // El relayer paga la tarifa de las autorizaciones firmadas.
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            assert_eq!(
+                contract.increase_with_signature(signer, 0, 5_000, INCREASE_SIGNATURE),
+                Err(CounterError::FeeTooLow)
+            );
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(10);
+            assert_eq!(contract.increase_with_signature(signer, 0, 5_000, INCREASE_SIGNATURE), Ok(()));
+            assert_eq!(contract.decrease_with_signature(signer, 1, 5_000, DECREASE_SIGNATURE), Ok(()));
+            assert_eq!(contract.collected_fees(), 40);
             assert_eq!(contract.retrieve_number(), 0);
         }
 
         #[ink::test]
-        #[should_panic(expected = "Caller is not in the white list")]
-        fn increase_only_whitelisted() {
-            use ink::env::test;
+        fn stats_and_top_contributors_are_tracked() {
+            // Obtenemos las cuentas por defecto y habilitamos a `bob` y `charlie`.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.add_to_white_list(accounts.bob, None), Ok(()));
+            assert_eq!(contract.add_to_white_list(accounts.charlie, None), Ok(()));
 
-            // Obtenemos las cuentas por defecto.
+            // `bob` incrementa dos veces y decrementa una, en el bloque 1.
+            test::advance_block::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.increase_number(), Ok(()));
+            assert_eq!(contract.increase_number(), Ok(()));
+            assert_eq!(contract.decrease_number(), Ok(()));
+
+            // `charlie` incrementa una vez, en el bloque 2.
+            test::advance_block::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.increase_number(), Ok(()));
+
+            assert_eq!(
+                contract.stats_of(accounts.bob),
+                AccountStats { increments: 2, decrements: 1, last_block: 1 }
+            );
+            assert_eq!(
+                contract.stats_of(accounts.charlie),
+                AccountStats { increments: 1, decrements: 0, last_block: 2 }
+            );
+            assert_eq!(contract.stats_of(accounts.django), AccountStats::default());
+
+            // El ranking está ordenado y respeta el límite pedido.
+            let top = contract.top_contributors(10);
+            assert_eq!(top.len(), 2);
+            assert_eq!(top[0].0, accounts.bob);
+            assert_eq!(top[1].0, accounts.charlie);
+            assert_eq!(contract.top_contributors(1).len(), 1);
+        }
+
+        #[ink::test]
+        fn leaderboard_is_bounded() {
+            // Creamos el contrato y le damos el rol de incrementador a muchas cuentas.
             let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            let contributors: Vec<AccountId> = (0..MAX_LEADERBOARD_SIZE + 5)
+                .map(|i| {
+                    let mut bytes = [0xAA; 32];
+                    bytes[..4].copy_from_slice(&i.to_le_bytes());
+                    AccountId::from(bytes)
+                })
+                .collect();
+            for contributor in &contributors {
+                assert_eq!(contract.grant_role(INCREMENTER_ROLE, *contributor), Ok(()));
+                test::set_caller::<ink::env::DefaultEnvironment>(*contributor);
+                assert_eq!(contract.increase_number(), Ok(()));
+                test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            }
 
-            // Configuramos al owner (alice) y creamos el contrato.
+            // Solo se guardan `MAX_LEADERBOARD_SIZE` cuentas, aunque se pidan más.
+            assert_eq!(contract.top_contributors(u32::MAX).len(), MAX_LEADERBOARD_SIZE as usize);
+
+            // La última cuenta sube al primer puesto al superar al resto.
+            let last = *contributors.last().unwrap();
+            test::set_caller::<ink::env::DefaultEnvironment>(last);
+            assert_eq!(contract.increase_number(), Ok(()));
+            assert_eq!(contract.top_contributors(1)[0].0, last);
+        }
+
+        #[ink::test]
+        fn reset_needs_approvals_before_deadline() {
+            // Obtenemos las cuentas por defecto; `bob` y `charlie` aprueban reinicios.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
             let mut contract = SimpleCounter::new();
+            assert_eq!(contract.set_number(42), Ok(()));
+            assert_eq!(contract.grant_role(RESET_APPROVER_ROLE, accounts.bob), Ok(()));
+            assert_eq!(contract.grant_role(RESET_APPROVER_ROLE, accounts.charlie), Ok(()));
+            assert_eq!(contract.set_reset_policy(0, 1_000), Err(CounterError::InvalidResetPolicy));
+            assert_eq!(contract.set_reset_policy(2, 1_000), Ok(()));
 
-            // No agregamos a bob a la whitelist.
-            // Cambiamos el caller a bob, que no está en la whitelist.
+            // Solo el owner propone.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.propose_reset(0), Err(CounterError::NotOwner));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.propose_reset(0), Ok(0));
+
+            // Solo los aprobadores aprueban, y una sola vez cada uno. Quien propone no puede
+            // aprobar aunque tenga el rol.
+            assert_eq!(contract.approve_reset(0), Err(CounterError::MissingRole));
+            assert_eq!(contract.grant_role(RESET_APPROVER_ROLE, accounts.alice), Ok(()));
+            assert_eq!(contract.approve_reset(0), Err(CounterError::ProposerCannotApprove));
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.approve_reset(1), Err(CounterError::NoResetProposal));
+            assert_eq!(contract.approve_reset(0), Ok(()));
+            assert_eq!(contract.approve_reset(0), Err(CounterError::AlreadyApproved));
 
-            // Se espera que al intentar aumentar el contador se genere un panic
-            // con el mensaje "Caller is not in the white list".
-            contract.increase_number();
+            // Con una sola aprobación no alcanza.
+            assert_eq!(contract.execute_reset(0), Err(CounterError::NotEnoughApprovals));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.approve_reset(0), Ok(()));
+
+            // Cualquiera puede ejecutar una propuesta aprobada.
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(contract.execute_reset(0), Ok(()));
+            assert_eq!(contract.retrieve_number(), 0);
+            assert_eq!(contract.reset_proposal(), None);
+            assert_eq!(contract.execute_reset(0), Err(CounterError::NoResetProposal));
         }
 
         #[ink::test]
-        #[should_panic(expected = "Caller is not in the white list")]
-        fn decrease_only_whitelisted() {
-            use ink::env::test;
+        fn reset_proposal_expires() {
+            // Creamos el contrato con `bob` como aprobador y un umbral de una aprobación.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.set_number(7), Ok(()));
+            assert_eq!(contract.grant_role(RESET_APPROVER_ROLE, accounts.bob), Ok(()));
+            assert_eq!(contract.set_reset_policy(1, 1_000), Ok(()));
 
-            // Obtenemos las cuentas por defecto.
+            // El valor propuesto tiene que respetar los límites.
+            assert_eq!(contract.set_bounds(0, 100), Ok(()));
+            assert_eq!(contract.propose_reset(101), Err(CounterError::OutOfBounds));
+            assert_eq!(contract.propose_reset(3), Ok(0));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.approve_reset(0), Ok(()));
+
+            // Pasado el plazo ya no se puede ejecutar.
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_001);
+            assert_eq!(contract.execute_reset(0), Err(CounterError::ResetProposalExpired));
+            assert_eq!(contract.retrieve_number(), 7);
+        }
+
+        #[ink::test]
+        fn default_reset_policy_needs_two_approvers() {
+            // `alice` se da el rol de aprobadora y se lo da a `bob`, sin configurar la política.
             let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = SimpleCounter::new();
+            assert_eq!(contract.set_number(5), Ok(()));
+            assert_eq!(contract.grant_role(RESET_APPROVER_ROLE, accounts.alice), Ok(()));
+            assert_eq!(contract.grant_role(RESET_APPROVER_ROLE, accounts.bob), Ok(()));
+            assert_eq!(contract.reset_policy().0, DEFAULT_RESET_THRESHOLD);
 
-            // Configuramos al owner (alice) y creamos el contrato.
+            // Una sola clave no alcanza: `alice` no puede aprobar su propia propuesta.
+            assert_eq!(contract.propose_reset(0), Ok(0));
+            assert_eq!(contract.approve_reset(0), Err(CounterError::ProposerCannotApprove));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.approve_reset(0), Ok(()));
+            assert_eq!(contract.execute_reset(0), Err(CounterError::NotEnoughApprovals));
+            assert_eq!(contract.retrieve_number(), 5);
+        }
+
+        #[ink::test]
+        fn revoked_approvers_do_not_count() {
+            // Creamos el contrato con `bob` y `charlie` como aprobadores y un umbral de dos.
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
             let mut contract = SimpleCounter::new();
+            assert_eq!(contract.set_number(9), Ok(()));
+            assert_eq!(contract.grant_role(RESET_APPROVER_ROLE, accounts.bob), Ok(()));
+            assert_eq!(contract.grant_role(RESET_APPROVER_ROLE, accounts.charlie), Ok(()));
+            assert_eq!(contract.set_reset_policy(2, 1_000), Ok(()));
+            assert_eq!(contract.propose_reset(0), Ok(0));
 
-            // No agregamos a bob a la whitelist.
-            // Cambiamos el caller a bob, que no está en la whitelist.
+            // Ambos aprueban, pero a `bob` le quitan el rol antes de ejecutar.
             test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.approve_reset(0), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.approve_reset(0), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.revoke_role(RESET_APPROVER_ROLE, accounts.bob), Ok(()));
+
+            // La aprobación de `bob` ya no cuenta.
+            assert_eq!(contract.execute_reset(0), Err(CounterError::NotEnoughApprovals));
+
+            // Una propuesta nueva arranca sin aprobaciones.
+            assert_eq!(contract.propose_reset(1), Ok(1));
+            assert_eq!(contract.reset_proposal().map(|proposal| proposal.approvers), Some(Vec::new()));
 
-            // Se espera que al intentar disminuir el contador se genere un panic
-            // con el mensaje "Caller is not in the white list".
-            contract.decrease_number();
+            // Con un nuevo aprobador se alcanza el umbral.
+            assert_eq!(contract.grant_role(RESET_APPROVER_ROLE, accounts.django), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.approve_reset(1), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(contract.approve_reset(1), Ok(()));
+            assert_eq!(contract.execute_reset(1), Ok(()));
+            assert_eq!(contract.retrieve_number(), 1);
+            assert_eq!(contract.reset_proposal(), None);
+        }
+
+        #[ink::test]
+        fn counter_trait_keeps_legacy_selectors() {
+            // Armamos las llamadas del trait sin ejecutarlas y comparamos sus selectores
+            // con los que tenían los mensajes antes de existir el trait.
+            let mut counter: ink::contract_ref!(Counter) = AccountId::from([0x07; 32]).into();
+            let increase = TraitCallBuilder::call_mut(&mut counter).increase_number().params();
+            assert_eq!(increase.exec_input().encode()[..4], ink::selector_bytes!("increase_number"));
+            let decrease = TraitCallBuilder::call_mut(&mut counter).decrease_number().params();
+            assert_eq!(decrease.exec_input().encode()[..4], ink::selector_bytes!("decrease_number"));
+            let retrieve = TraitCallBuilder::call(&counter).retrieve_number().params();
+            assert_eq!(retrieve.exec_input().encode()[..4], ink::selector_bytes!("retrieve_number"));
         }
 
     }    
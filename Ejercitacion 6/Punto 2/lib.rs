@@ -2,8 +2,229 @@
 
 #[ink::contract]
 mod personal_wallet {
-    use ink::prelude::string::ToString;
-    use ink::prelude::string::String;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::prelude::vec::Vec;
+    use ink::scale::{Encode, Decode, Output};
+    use ink::storage::Mapping;
+    use scale_info::TypeInfo;
+
+    /// Identifier of a multisig transaction.
+    pub type TransactionId = u32;
+
+    /// Maximum number of multisig signers, since confirmations are counted over all of them.
+    pub const MAX_SIGNERS: u32 = 20;
+
+    /// Identifier of a payment above the spending limits.
+    pub type PaymentId = u32;
+
+    /// Length (ms) of the rolling window of the daily limit.
+    pub const DAY: Timestamp = 86_400_000;
+    /// Minimum `SpendingLimits::delay` while a per-transaction or daily limit is set,
+    /// so payments above them can never be executed right after being requested.
+    pub const MIN_LIMITS_DELAY: Timestamp = 3_600_000;
+
+    /// Identifier of a scheduled payment.
+    pub type ScheduleId = u32;
+
+    /// Maximum number of scheduled payments waiting to be executed.
+    pub const MAX_PENDING_SCHEDULES: u32 = 100;
+    /// Maximum number of entries returned by the paginated queries.
+    pub const MAX_PAGE_SIZE: u32 = 50;
+
+    /// Identifier of a subscription.
+    pub type SubscriptionId = u32;
+    /// Shortest subscription period, so a payee cannot drain the wallet with tiny periods.
+    pub const MIN_SUBSCRIPTION_PERIOD: Timestamp = DAY;
+
+    /// Identifier of a recovery attempt.
+    pub type RecoveryId = u32;
+
+    /// Maximum number of guardians.
+    pub const MAX_GUARDIANS: u32 = 10;
+    /// Shortest recovery delay, so the owner always has time to cancel a recovery.
+    pub const MIN_RECOVERY_DELAY: Timestamp = DAY;
+
+    /// Errors returned by the wallet messages, so clients can decode the
+    /// failure reason instead of comparing strings.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+    pub enum WalletError {
+        /// The caller is not the owner of the wallet.
+        NotOwner,
+        /// The contract balance does not cover the amount.
+        InsufficientBalance,
+        /// The chain rejected the transfer, e.g. because it would leave the
+        /// contract below the existential deposit.
+        TransferFailed,
+        /// The tracked balance would overflow.
+        Overflow,
+        /// Multisig mode is on, so funds can only leave through `execute`.
+        MultisigEnabled,
+        /// Multisig mode is off.
+        MultisigDisabled,
+        /// The caller is not a multisig signer.
+        NotSigner,
+        /// The threshold has to be between 1 and the number of signers.
+        InvalidThreshold,
+        /// The account is already a signer.
+        AlreadySigner,
+        /// There are already `MAX_SIGNERS` signers.
+        TooManySigners,
+        /// There is no transaction with the given id.
+        UnknownTransaction,
+        /// The transaction was already executed.
+        AlreadyExecuted,
+        /// The caller already confirmed the transaction.
+        AlreadyConfirmed,
+        /// The caller has not confirmed the transaction.
+        NotConfirmed,
+        /// The transaction does not have enough confirmations from current signers.
+        NotEnoughConfirmations,
+        /// `data` has to start with a 4 byte selector.
+        InvalidCallData,
+        /// The call to the destination contract failed.
+        CallFailed,
+        /// The amount is above the per-transaction limit or the remaining daily allowance.
+        LimitExceeded,
+        /// The caller is not the secondary approver.
+        NotApprover,
+        /// There is no pending payment with the given id.
+        UnknownPayment,
+        /// The payment is neither approved nor past its delay.
+        PaymentLocked,
+        /// Limits are set with a delay below `MIN_LIMITS_DELAY`.
+        InvalidLimits,
+        /// There is no pending change of the spending limits.
+        NoPendingLimits,
+        /// The pending change of the spending limits is neither approved nor past its delay.
+        LimitsLocked,
+        /// There is no pending scheduled payment with the given id.
+        UnknownSchedule,
+        /// The scheduled payment or subscription period is not due yet.
+        NotDue,
+        /// There are already `MAX_PENDING_SCHEDULES` pending scheduled payments.
+        TooManySchedules,
+        /// The execution time of a scheduled payment has to be in the future.
+        InvalidSchedule,
+        /// There is no active subscription with the given id.
+        UnknownSubscription,
+        /// The caller is not the payee of the subscription.
+        NotPayee,
+        /// The period has to be at least `MIN_SUBSCRIPTION_PERIOD` and the maximum
+        /// number of periods greater than zero.
+        InvalidSubscription,
+        /// The caller is not a guardian.
+        NotGuardian,
+        /// The account is already a guardian.
+        AlreadyGuardian,
+        /// There are already `MAX_GUARDIANS` guardians.
+        TooManyGuardians,
+        /// A recovery is already in progress.
+        RecoveryInProgress,
+        /// There is no recovery in progress with the given id.
+        NoRecovery,
+        /// The caller already supports the recovery.
+        AlreadySupported,
+        /// The recovery does not have enough guardian support yet.
+        NotEnoughSupport,
+        /// The recovery delay has not passed yet.
+        RecoveryLocked,
+        /// Guardians need a recovery delay of at least `MIN_RECOVERY_DELAY`.
+        InvalidRecoveryDelay,
+        /// The amount to send has to be greater than zero.
+        ZeroAmount,
+    }
+
+    /// Limits on what the owner can send with `send_coin`. `None` means no limit.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug, Default)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct SpendingLimits {
+        pub per_transaction: Option<Balance>,
+        /// Maximum total sent in any `DAY` long window.
+        pub daily: Option<Balance>,
+        /// Time (ms) a payment above the limits waits unless `approver` approves it.
+        pub delay: Timestamp,
+        pub approver: Option<AccountId>,
+    }
+
+    /// Looser spending limits waiting for the current delay or the current approver.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct PendingLimits {
+        pub limits: SpendingLimits,
+        pub unlock_at: Timestamp,
+    }
+
+    /// Payment to `to` that anyone can execute from `execute_after` on.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct ScheduledPayment {
+        pub to: AccountId,
+        pub amount: Balance,
+        pub execute_after: Timestamp,
+    }
+
+    /// Authorization for `payee` to pull `amount` once every `period` ms, at most `max_periods` times.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Subscription {
+        pub payee: AccountId,
+        pub amount: Balance,
+        pub period: Timestamp,
+        pub max_periods: u32,
+        pub collected_periods: u32,
+        /// When the next period can be collected.
+        pub next_collection: Timestamp,
+    }
+
+    /// Attempt by the guardians to hand the wallet to `new_owner`.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Recovery {
+        pub id: RecoveryId,
+        pub new_owner: AccountId,
+        pub supporters: u32,
+        /// From this moment on the recovery can be completed, if it has enough support.
+        pub unlock_at: Timestamp,
+    }
+
+    /// Payment above the spending limits, waiting for its delay or the approver.
+    #[derive(Encode, Decode, TypeInfo, Clone, Copy, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct PendingPayment {
+        pub to: AccountId,
+        pub amount: Balance,
+        pub unlock_at: Timestamp,
+        pub approved: bool,
+    }
+
+    /// What a multisig transaction does once executed.
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum Action {
+        /// Sends `amount` to `to`. A non-empty `data` (selector followed by the
+        /// encoded arguments) calls `to` as a contract instead of a plain transfer.
+        Transfer { to: AccountId, amount: Balance, data: Vec<u8> },
+        AddSigner(AccountId),
+        RemoveSigner(AccountId),
+        ChangeThreshold(u32),
+        CancelSubscription(SubscriptionId),
+    }
+
+    #[derive(Encode, Decode, TypeInfo, Clone, PartialEq, Eq, Debug)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Transaction {
+        pub action: Action,
+        pub executed: bool,
+    }
+
+    /// Call arguments that are already encoded, written as they are.
+    struct CallInput<'a>(&'a [u8]);
+
+    impl Encode for CallInput<'_> {
+        fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+            dest.write(self.0);
+        }
+    }
 
     /// Defines the storage of your contract.
     /// Add new fields to the below struct in order
@@ -11,65 +232,1096 @@ mod personal_wallet {
     #[ink(storage)]
     pub struct PersonalWallet {
         owner: AccountId,
+        /// Funds accounted through `deposit` and `send_coin`. The real balance is
+        /// `self.env().balance()`, which also includes plain transfers and the
+        /// existential deposit; `sync_balance` brings this back in line with it.
         balance: Balance,
+        /// Multisig signers. Empty while multisig mode is off.
+        signers: Vec<AccountId>,
+        /// Confirmations needed to execute a transaction. `0` while multisig mode is off.
+        threshold: u32,
+        transactions: Mapping<TransactionId, Transaction>,
+        confirmations: Mapping<(TransactionId, AccountId), ()>,
+        next_transaction_id: TransactionId,
+        limits: SpendingLimits,
+        /// Looser limits requested by the owner, not applied yet.
+        pending_limits: Option<PendingLimits>,
+        /// Time and amount of the payments made in the last `DAY`, oldest first.
+        /// Only kept while there is a daily limit.
+        recent_spends: Vec<(Timestamp, Balance)>,
+        pending_payments: Mapping<PaymentId, PendingPayment>,
+        next_payment_id: PaymentId,
+        /// Funds set aside for scheduled payments; they cannot be spent otherwise.
+        reserved: Balance,
+        schedules: Mapping<ScheduleId, ScheduledPayment>,
+        /// Ids of the scheduled payments not yet executed or cancelled, oldest first.
+        pending_schedules: Vec<ScheduleId>,
+        next_schedule_id: ScheduleId,
+        subscriptions: Mapping<SubscriptionId, Subscription>,
+        next_subscription_id: SubscriptionId,
+        /// Accounts that can move the wallet to a new owner if the owner key is lost.
+        guardians: Vec<AccountId>,
+        /// Guardians that have to support a recovery. `0` while there are no guardians.
+        recovery_threshold: u32,
+        /// Time (ms) the current owner has to cancel a recovery.
+        recovery_delay: Timestamp,
+        recovery: Option<Recovery>,
+        recovery_supporters: Mapping<(RecoveryId, AccountId), ()>,
+        next_recovery_id: RecoveryId,
     }
 
-    #[ink(event)]
-    pub struct CoinReceived {
-        #[ink(topic)]
-        sender: AccountId,
-        amount: Balance,
-    }
+    #[ink(event)]
+    pub struct CoinReceived {
+        #[ink(topic)]
+        sender: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct BalanceSynced {
+        previous: Balance,
+        current: Balance,
+    }
+
+    #[ink(event)]
+    pub struct SpendingLimitsChanged {
+        limits: SpendingLimits,
+    }
+
+    #[ink(event)]
+    pub struct SpendingLimitsRequested {
+        limits: SpendingLimits,
+        unlock_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct SpendingLimitsCancelled {
+        limits: SpendingLimits,
+    }
+
+    #[ink(event)]
+    pub struct PaymentRequested {
+        #[ink(topic)]
+        id: PaymentId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+        unlock_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct PaymentApproved {
+        #[ink(topic)]
+        id: PaymentId,
+    }
+
+    #[ink(event)]
+    pub struct PaymentExecuted {
+        #[ink(topic)]
+        id: PaymentId,
+    }
+
+    #[ink(event)]
+    pub struct PaymentCancelled {
+        #[ink(topic)]
+        id: PaymentId,
+    }
+
+    #[ink(event)]
+    pub struct PaymentScheduled {
+        #[ink(topic)]
+        id: ScheduleId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+        execute_after: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct ScheduledPaymentExecuted {
+        #[ink(topic)]
+        id: ScheduleId,
+        executor: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ScheduledPaymentCancelled {
+        #[ink(topic)]
+        id: ScheduleId,
+    }
+
+    #[ink(event)]
+    pub struct SubscriptionCreated {
+        #[ink(topic)]
+        id: SubscriptionId,
+        #[ink(topic)]
+        payee: AccountId,
+        amount: Balance,
+        period: Timestamp,
+        max_periods: u32,
+    }
+
+    #[ink(event)]
+    pub struct SubscriptionCollected {
+        #[ink(topic)]
+        id: SubscriptionId,
+        #[ink(topic)]
+        payee: AccountId,
+        amount: Balance,
+        /// Number of periods collected so far, including this one.
+        collected_periods: u32,
+    }
+
+    #[ink(event)]
+    pub struct SubscriptionCancelled {
+        #[ink(topic)]
+        id: SubscriptionId,
+    }
+
+    #[ink(event)]
+    pub struct GuardiansChanged {
+        guardians: Vec<AccountId>,
+        threshold: u32,
+        delay: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct RecoveryInitiated {
+        #[ink(topic)]
+        id: RecoveryId,
+        #[ink(topic)]
+        new_owner: AccountId,
+        guardian: AccountId,
+        unlock_at: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct RecoverySupported {
+        #[ink(topic)]
+        id: RecoveryId,
+        #[ink(topic)]
+        guardian: AccountId,
+        supporters: u32,
+    }
+
+    #[ink(event)]
+    pub struct RecoveryCancelled {
+        #[ink(topic)]
+        id: RecoveryId,
+    }
+
+    #[ink(event)]
+    pub struct OwnerRecovered {
+        #[ink(topic)]
+        id: RecoveryId,
+        #[ink(topic)]
+        previous_owner: AccountId,
+        #[ink(topic)]
+        new_owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct MultisigEnabled {
+        signers: Vec<AccountId>,
+        threshold: u32,
+    }
+
+    #[ink(event)]
+    pub struct TransactionSubmitted {
+        #[ink(topic)]
+        id: TransactionId,
+        #[ink(topic)]
+        signer: AccountId,
+        action: Action,
+    }
+
+    #[ink(event)]
+    pub struct TransactionConfirmed {
+        #[ink(topic)]
+        id: TransactionId,
+        #[ink(topic)]
+        signer: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ConfirmationRevoked {
+        #[ink(topic)]
+        id: TransactionId,
+        #[ink(topic)]
+        signer: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct TransactionExecuted {
+        #[ink(topic)]
+        id: TransactionId,
+        #[ink(topic)]
+        signer: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct SignerAdded {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct SignerRemoved {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ThresholdChanged {
+        threshold: u32,
+    }
+
+    impl PersonalWallet {
+        /// Constructor that initializes the `bool` value to the given `init_value`.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let caller = Self::env().caller();
+            Self { owner : caller,
+                    balance : 0,
+                    signers : Vec::new(),
+                    threshold : 0,
+                    transactions : Mapping::new(),
+                    confirmations : Mapping::new(),
+                    next_transaction_id : 0,
+                    limits : SpendingLimits::default(),
+                    pending_limits : None,
+                    recent_spends : Vec::new(),
+                    pending_payments : Mapping::new(),
+                    next_payment_id : 0,
+                    reserved : 0,
+                    schedules : Mapping::new(),
+                    pending_schedules : Vec::new(),
+                    next_schedule_id : 0,
+                    subscriptions : Mapping::new(),
+                    next_subscription_id : 0,
+                    guardians : Vec::new(),
+                    recovery_threshold : 0,
+                    recovery_delay : 0,
+                    recovery : None,
+                    recovery_supporters : Mapping::new(),
+                    next_recovery_id : 0 }
+        }
+
+        #[ink(message, payable)]
+        pub fn deposit(&mut self) -> Result<(), WalletError> {
+            let caller = self.env().caller();
+            let amount = self.env().transferred_value();
+
+            self.balance = self.balance.checked_add(amount).ok_or(WalletError::Overflow)?;
+
+            self.env().emit_event(CoinReceived { sender: caller, amount });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn send_coin(&mut self, to: AccountId, amount: Balance) -> Result<(), WalletError> {
+            self.ensure_owner_spending()?;
+            if amount == 0 {
+                return Err(WalletError::ZeroAmount);
+            }
+
+            // Por encima de los límites hay que usar `request_payment`
+            if !self.within_limits(amount) {
+                return Err(WalletError::LimitExceeded);
+            }
+
+            // Verificar si el contrato tiene suficiente saldo real, sin contar lo reservado
+            if self.available_balance() < amount {
+                return Err(WalletError::InsufficientBalance);
+            }
+
+            // Intentar la transferencia
+            self.env()
+                .transfer(to, amount)
+                .map_err(|_| WalletError::TransferFailed)?;
+
+            // Actualizar el balance registrado; puede ser menor que el real si hubo fondos sin registrar
+            self.balance = self.balance.saturating_sub(amount);
+            self.record_spend(amount);
+
+            Ok(())
+        }
+
+        /// Tighter limits apply right away. Looser ones (a higher or removed limit, a
+        /// shorter delay or a new, different or removed approver) are queued: they apply with
+        /// `apply_spending_limits` once the current delay passes, or right away when the
+        /// current approver calls `approve_spending_limits`. Either way any queued change is replaced.
+        #[ink(message)]
+        pub fn set_spending_limits(&mut self, limits: SpendingLimits) -> Result<(), WalletError> {
+            self.ensure_owner()?;
+            let limited = limits.per_transaction.is_some() || limits.daily.is_some();
+            if limited && limits.delay < MIN_LIMITS_DELAY {
+                return Err(WalletError::InvalidLimits);
+            }
+            if let Some(pending) = self.pending_limits.take() {
+                self.env().emit_event(SpendingLimitsCancelled { limits: pending.limits });
+            }
+            if !self.loosens_limits(&limits) {
+                self.limits = limits;
+                self.env().emit_event(SpendingLimitsChanged { limits });
+                return Ok(());
+            }
+            let unlock_at = self.env().block_timestamp().saturating_add(self.limits.delay);
+            self.pending_limits = Some(PendingLimits { limits, unlock_at });
+            self.env().emit_event(SpendingLimitsRequested { limits, unlock_at });
+            Ok(())
+        }
+
+        /// Applies the queued limits once the delay of the current limits passed.
+        #[ink(message)]
+        pub fn apply_spending_limits(&mut self) -> Result<(), WalletError> {
+            self.ensure_owner()?;
+            let pending = self.pending_limits.ok_or(WalletError::NoPendingLimits)?;
+            if self.env().block_timestamp() < pending.unlock_at {
+                return Err(WalletError::LimitsLocked);
+            }
+            self.pending_limits = None;
+            self.limits = pending.limits;
+            self.env().emit_event(SpendingLimitsChanged { limits: pending.limits });
+            Ok(())
+        }
+
+        /// Lets the current approver apply the queued limits without waiting.
+        #[ink(message)]
+        pub fn approve_spending_limits(&mut self) -> Result<(), WalletError> {
+            self.ensure_approver()?;
+            let pending = self.pending_limits.take().ok_or(WalletError::NoPendingLimits)?;
+            self.limits = pending.limits;
+            self.env().emit_event(SpendingLimitsChanged { limits: pending.limits });
+            Ok(())
+        }
+
+        /// Drops the queued limits. Callable by the owner or the current approver.
+        #[ink(message)]
+        pub fn cancel_spending_limits(&mut self) -> Result<(), WalletError> {
+            self.ensure_owner_or_approver()?;
+            let pending = self.pending_limits.take().ok_or(WalletError::NoPendingLimits)?;
+            self.env().emit_event(SpendingLimitsCancelled { limits: pending.limits });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn spending_limits(&self) -> SpendingLimits {
+            self.limits
+        }
+
+        #[ink(message)]
+        pub fn pending_spending_limits(&self) -> Option<PendingLimits> {
+            self.pending_limits
+        }
+
+        /// What `send_coin` can still send in the current rolling day, `None` without daily limit.
+        #[ink(message)]
+        pub fn remaining_allowance_today(&self) -> Option<Balance> {
+            let daily = self.limits.daily?;
+            Some(daily.saturating_sub(self.spent_today()))
+        }
+
+        /// Queues a payment above the limits. It can be executed once `delay` passes,
+        /// or right away if the approver approves it.
+        #[ink(message)]
+        pub fn request_payment(&mut self, to: AccountId, amount: Balance) -> Result<PaymentId, WalletError> {
+            self.ensure_owner_spending()?;
+            if amount == 0 {
+                return Err(WalletError::ZeroAmount);
+            }
+            let id = self.next_payment_id;
+            self.next_payment_id = id.checked_add(1).ok_or(WalletError::Overflow)?;
+            let unlock_at = self.env().block_timestamp().saturating_add(self.limits.delay);
+            self.pending_payments.insert(id, &PendingPayment { to, amount, unlock_at, approved: false });
+            self.env().emit_event(PaymentRequested { id, to, amount, unlock_at });
+            Ok(id)
+        }
+
+        #[ink(message)]
+        pub fn approve_payment(&mut self, id: PaymentId) -> Result<(), WalletError> {
+            self.ensure_approver()?;
+            let mut payment = self.pending_payments.get(id).ok_or(WalletError::UnknownPayment)?;
+            payment.approved = true;
+            self.pending_payments.insert(id, &payment);
+            self.env().emit_event(PaymentApproved { id });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn execute_payment(&mut self, id: PaymentId) -> Result<(), WalletError> {
+            self.ensure_owner_spending()?;
+            let payment = self.pending_payments.get(id).ok_or(WalletError::UnknownPayment)?;
+            if !payment.approved && self.env().block_timestamp() < payment.unlock_at {
+                return Err(WalletError::PaymentLocked);
+            }
+            self.transfer_out(payment.to, payment.amount, &[])?;
+            self.record_spend(payment.amount);
+            self.pending_payments.remove(id);
+            self.env().emit_event(PaymentExecuted { id });
+            Ok(())
+        }
+
+        /// Drops a pending payment. Callable by the owner or, as a veto, by the approver.
+        #[ink(message)]
+        pub fn cancel_payment(&mut self, id: PaymentId) -> Result<(), WalletError> {
+            self.ensure_owner_or_approver()?;
+            if !self.pending_payments.contains(id) {
+                return Err(WalletError::UnknownPayment);
+            }
+            self.pending_payments.remove(id);
+            self.env().emit_event(PaymentCancelled { id });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn pending_payment(&self, id: PaymentId) -> Option<PendingPayment> {
+            self.pending_payments.get(id)
+        }
+
+        /// Reserves `amount` for a payment to `to` that anyone can execute with
+        /// `execute_due` from `execute_after` on. `amount` has to fit the per-transaction
+        /// and daily limits; the daily allowance is checked again when it is executed.
+        #[ink(message)]
+        pub fn schedule_payment(&mut self, to: AccountId, amount: Balance, execute_after: Timestamp) -> Result<ScheduleId, WalletError> {
+            self.ensure_owner_spending()?;
+            if execute_after <= self.env().block_timestamp() {
+                return Err(WalletError::InvalidSchedule);
+            }
+            if self.limits.per_transaction.is_some_and(|limit| amount > limit)
+                || self.limits.daily.is_some_and(|limit| amount > limit)
+            {
+                return Err(WalletError::LimitExceeded);
+            }
+            if self.available_balance() < amount {
+                return Err(WalletError::InsufficientBalance);
+            }
+            if self.pending_schedules.len() >= MAX_PENDING_SCHEDULES as usize {
+                return Err(WalletError::TooManySchedules);
+            }
+            let id = self.next_schedule_id;
+            self.next_schedule_id = id.checked_add(1).ok_or(WalletError::Overflow)?;
+            self.reserved = self.reserved.saturating_add(amount);
+            self.schedules.insert(id, &ScheduledPayment { to, amount, execute_after });
+            self.pending_schedules.push(id);
+            self.env().emit_event(PaymentScheduled { id, to, amount, execute_after });
+            Ok(id)
+        }
+
+        /// Releases the funds reserved for a scheduled payment.
+        #[ink(message)]
+        pub fn cancel_scheduled(&mut self, id: ScheduleId) -> Result<(), WalletError> {
+            self.ensure_owner()?;
+            let payment = self.remove_schedule(id)?;
+            self.reserved = self.reserved.saturating_sub(payment.amount);
+            self.env().emit_event(ScheduledPaymentCancelled { id });
+            Ok(())
+        }
+
+        /// Executes a scheduled payment once its time has come. Anyone can call it.
+        /// It counts towards the daily limit, so it fails while the allowance does not
+        /// cover it, and it is disabled in multisig mode.
+        #[ink(message)]
+        pub fn execute_due(&mut self, id: ScheduleId) -> Result<(), WalletError> {
+            if self.threshold > 0 {
+                return Err(WalletError::MultisigEnabled);
+            }
+            let payment = self.schedules.get(id).ok_or(WalletError::UnknownSchedule)?;
+            if self.env().block_timestamp() < payment.execute_after {
+                return Err(WalletError::NotDue);
+            }
+            if !self.within_limits(payment.amount) {
+                return Err(WalletError::LimitExceeded);
+            }
+            self.remove_schedule(id)?;
+            self.reserved = self.reserved.saturating_sub(payment.amount);
+            self.transfer_out(payment.to, payment.amount, &[])?;
+            self.record_spend(payment.amount);
+            self.env().emit_event(ScheduledPaymentExecuted { id, executor: self.env().caller() });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn scheduled_payment(&self, id: ScheduleId) -> Option<ScheduledPayment> {
+            self.schedules.get(id)
+        }
+
+        #[ink(message)]
+        pub fn pending_schedules_len(&self) -> u32 {
+            self.pending_schedules.len() as u32
+        }
+
+        /// Pending scheduled payments from `offset`, oldest first. `limit` is capped at `MAX_PAGE_SIZE`.
+        #[ink(message)]
+        pub fn pending_schedules(&self, offset: u32, limit: u32) -> Vec<(ScheduleId, ScheduledPayment)> {
+            self.pending_schedules
+                .iter()
+                .skip(offset as usize)
+                .take(limit.min(MAX_PAGE_SIZE) as usize)
+                .filter_map(|id| self.schedules.get(id).map(|payment| (*id, payment)))
+                .collect()
+        }
+
+        /// Lets `payee` collect `amount` every `period` ms, starting one period from now,
+        /// up to `max_periods` times. `amount` has to fit the per-transaction and daily limits.
+        #[ink(message)]
+        pub fn create_subscription(&mut self, payee: AccountId, amount: Balance, period: Timestamp, max_periods: u32) -> Result<SubscriptionId, WalletError> {
+            self.ensure_owner_spending()?;
+            if period < MIN_SUBSCRIPTION_PERIOD || max_periods == 0 {
+                return Err(WalletError::InvalidSubscription);
+            }
+            if self.limits.per_transaction.is_some_and(|limit| amount > limit)
+                || self.limits.daily.is_some_and(|limit| amount > limit)
+            {
+                return Err(WalletError::LimitExceeded);
+            }
+            let id = self.next_subscription_id;
+            self.next_subscription_id = id.checked_add(1).ok_or(WalletError::Overflow)?;
+            let next_collection = self.env().block_timestamp().saturating_add(period);
+            self.subscriptions.insert(id, &Subscription {
+                payee,
+                amount,
+                period,
+                max_periods,
+                collected_periods: 0,
+                next_collection,
+            });
+            self.env().emit_event(SubscriptionCreated { id, payee, amount, period, max_periods });
+            Ok(id)
+        }
+
+        /// Pays one period to the payee, who has to be the caller. Periods that were
+        /// not collected in time can be collected later, one per call, as long as the
+        /// daily limit allows. Disabled in multisig mode.
+        #[ink(message)]
+        pub fn collect(&mut self, id: SubscriptionId) -> Result<(), WalletError> {
+            if self.threshold > 0 {
+                return Err(WalletError::MultisigEnabled);
+            }
+            let mut subscription = self.subscriptions.get(id).ok_or(WalletError::UnknownSubscription)?;
+            if self.env().caller() != subscription.payee {
+                return Err(WalletError::NotPayee);
+            }
+            if self.env().block_timestamp() < subscription.next_collection {
+                return Err(WalletError::NotDue);
+            }
+            if !self.within_limits(subscription.amount) {
+                return Err(WalletError::LimitExceeded);
+            }
+            self.transfer_out(subscription.payee, subscription.amount, &[])?;
+            self.record_spend(subscription.amount);
+            subscription.collected_periods += 1;
+            subscription.next_collection = subscription.next_collection.saturating_add(subscription.period);
+            // Después del último período la suscripción termina
+            if subscription.collected_periods >= subscription.max_periods {
+                self.subscriptions.remove(id);
+            } else {
+                self.subscriptions.insert(id, &subscription);
+            }
+            self.env().emit_event(SubscriptionCollected {
+                id,
+                payee: subscription.payee,
+                amount: subscription.amount,
+                collected_periods: subscription.collected_periods,
+            });
+            Ok(())
+        }
+
+        /// Ends a subscription. In multisig mode the signers can also do it with
+        /// `submit_cancel_subscription`.
+        #[ink(message)]
+        pub fn cancel_subscription(&mut self, id: SubscriptionId) -> Result<(), WalletError> {
+            self.ensure_owner()?;
+            self.end_subscription(id)
+        }
+
+        #[ink(message)]
+        pub fn subscription(&self, id: SubscriptionId) -> Option<Subscription> {
+            self.subscriptions.get(id)
+        }
+
+        /// Funds reserved for scheduled payments.
+        #[ink(message)]
+        pub fn reserved_balance(&self) -> Balance {
+            self.reserved
+        }
+
+        /// Real balance minus the reserved funds: what can be sent right now.
+        #[ink(message)]
+        pub fn available_balance(&self) -> Balance {
+            self.env().balance().saturating_sub(self.reserved)
+        }
+
+        /// Real balance of the contract, including funds that did not go through `deposit`.
+        #[ink(message)]
+        pub fn get_balance(&self) -> Balance {
+            self.env().balance()
+        }
+
+        /// Balance accounted through `deposit` and `send_coin`.
+        #[ink(message)]
+        pub fn tracked_balance(&self) -> Balance {
+            self.balance
+        }
+
+        /// Funds held by the contract that are not reflected in `tracked_balance`.
+        #[ink(message)]
+        pub fn untracked_balance(&self) -> Balance {
+            self.env().balance().saturating_sub(self.balance)
+        }
+
+        /// Sets the tracked balance to the real one and returns it. Only the owner
+        /// can call it; anyone can read the difference with `untracked_balance`.
+        #[ink(message)]
+        pub fn sync_balance(&mut self) -> Result<Balance, WalletError> {
+            self.ensure_owner()?;
+            let previous = self.balance;
+            let current = self.env().balance();
+            self.balance = current;
+            self.env().emit_event(BalanceSynced { previous, current });
+            Ok(current)
+        }
+
+        /// Turns on multisig mode. From then on `send_coin`, `execute_due` and `collect` are
+        /// disabled and every transfer or signer change needs `threshold` confirmations.
+        /// Transfers still have to fit the spending limits, so weakening them keeps
+        /// going through `set_spending_limits`. Scheduled payments keep their reserve
+        /// until the owner cancels them.
+        #[ink(message)]
+        pub fn enable_multisig(&mut self, signers: Vec<AccountId>, threshold: u32) -> Result<(), WalletError> {
+            self.ensure_owner()?;
+            if self.threshold > 0 {
+                return Err(WalletError::MultisigEnabled);
+            }
+            if signers.len() > MAX_SIGNERS as usize {
+                return Err(WalletError::TooManySigners);
+            }
+            for (index, signer) in signers.iter().enumerate() {
+                if signers[..index].contains(signer) {
+                    return Err(WalletError::AlreadySigner);
+                }
+            }
+            if threshold == 0 || threshold as usize > signers.len() {
+                return Err(WalletError::InvalidThreshold);
+            }
+            self.signers = signers.clone();
+            self.threshold = threshold;
+            self.env().emit_event(MultisigEnabled { signers, threshold });
+            Ok(())
+        }
+
+        /// Proposes a transfer; `data` is optional call data for contract destinations.
+        /// The submitter's confirmation is recorded right away.
+        #[ink(message)]
+        pub fn submit_transaction(&mut self, to: AccountId, amount: Balance, data: Vec<u8>) -> Result<TransactionId, WalletError> {
+            if !data.is_empty() && data.len() < 4 {
+                return Err(WalletError::InvalidCallData);
+            }
+            self.submit(Action::Transfer { to, amount, data })
+        }
+
+        #[ink(message)]
+        pub fn submit_add_signer(&mut self, account: AccountId) -> Result<TransactionId, WalletError> {
+            self.submit(Action::AddSigner(account))
+        }
+
+        #[ink(message)]
+        pub fn submit_remove_signer(&mut self, account: AccountId) -> Result<TransactionId, WalletError> {
+            self.submit(Action::RemoveSigner(account))
+        }
+
+        #[ink(message)]
+        pub fn submit_change_threshold(&mut self, threshold: u32) -> Result<TransactionId, WalletError> {
+            self.submit(Action::ChangeThreshold(threshold))
+        }
+
+        #[ink(message)]
+        pub fn submit_cancel_subscription(&mut self, id: SubscriptionId) -> Result<TransactionId, WalletError> {
+            self.submit(Action::CancelSubscription(id))
+        }
+
+        #[ink(message)]
+        pub fn confirm(&mut self, id: TransactionId) -> Result<(), WalletError> {
+            let signer = self.ensure_signer()?;
+            self.ensure_pending(id)?;
+            if self.confirmations.contains((id, signer)) {
+                return Err(WalletError::AlreadyConfirmed);
+            }
+            self.confirmations.insert((id, signer), &());
+            self.env().emit_event(TransactionConfirmed { id, signer });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn revoke_confirmation(&mut self, id: TransactionId) -> Result<(), WalletError> {
+            let signer = self.ensure_signer()?;
+            self.ensure_pending(id)?;
+            if !self.confirmations.contains((id, signer)) {
+                return Err(WalletError::NotConfirmed);
+            }
+            self.confirmations.remove((id, signer));
+            self.env().emit_event(ConfirmationRevoked { id, signer });
+            Ok(())
+        }
+
+        /// Executes a transaction confirmed by at least `threshold` current signers.
+        #[ink(message)]
+        pub fn execute(&mut self, id: TransactionId) -> Result<(), WalletError> {
+            let signer = self.ensure_signer()?;
+            let mut transaction = self.ensure_pending(id)?;
+            if self.confirmation_count(id) < self.threshold {
+                return Err(WalletError::NotEnoughConfirmations);
+            }
+            match &transaction.action {
+                Action::Transfer { to, amount, data } => {
+                    // Los signers tampoco pueden saltarse los límites del owner
+                    if !self.within_limits(*amount) {
+                        return Err(WalletError::LimitExceeded);
+                    }
+                    self.transfer_out(*to, *amount, data)?;
+                    self.record_spend(*amount);
+                }
+                Action::AddSigner(account) => self.add_signer(*account)?,
+                Action::RemoveSigner(account) => self.remove_signer(*account)?,
+                Action::ChangeThreshold(threshold) => self.change_threshold(*threshold)?,
+                Action::CancelSubscription(subscription) => self.end_subscription(*subscription)?,
+            }
+            transaction.executed = true;
+            self.transactions.insert(id, &transaction);
+            self.env().emit_event(TransactionExecuted { id, signer });
+            Ok(())
+        }
 
-    impl PersonalWallet {
-        /// Constructor that initializes the `bool` value to the given `init_value`.
-        #[ink(constructor)]
-        pub fn new() -> Self {
-            let caller = Self::env().caller();
-            Self { owner : caller,
-                    balance : 0 }
+        #[ink(message)]
+        pub fn signers(&self) -> Vec<AccountId> {
+            self.signers.clone()
         }
 
-        #[ink(message, payable)]
-        pub fn deposit(&mut self) {
-            let caller = self.env().caller();
-            let amount = self.env().transferred_value();
+        #[ink(message)]
+        pub fn threshold(&self) -> u32 {
+            self.threshold
+        }
 
-            self.balance = self.balance.checked_add(amount).ok_or("Desbordamiento en el balance").unwrap();
+        #[ink(message)]
+        pub fn transaction(&self, id: TransactionId) -> Option<Transaction> {
+            self.transactions.get(id)
+        }
 
-            self.env().emit_event(CoinReceived { sender: caller, amount });
+        /// Confirmations of `id` from accounts that are still signers.
+        #[ink(message)]
+        pub fn confirmation_count(&self, id: TransactionId) -> u32 {
+            self.signers
+                .iter()
+                .filter(|signer| self.confirmations.contains((id, **signer)))
+                .count() as u32
+        }
+
+        #[ink(message)]
+        pub fn is_confirmed_by(&self, id: TransactionId, signer: AccountId) -> bool {
+            self.confirmations.contains((id, signer))
+        }
+
+        fn submit(&mut self, action: Action) -> Result<TransactionId, WalletError> {
+            if self.threshold == 0 {
+                return Err(WalletError::MultisigDisabled);
+            }
+            let signer = self.ensure_signer()?;
+            let id = self.next_transaction_id;
+            self.next_transaction_id = id.checked_add(1).ok_or(WalletError::Overflow)?;
+            self.transactions.insert(id, &Transaction { action: action.clone(), executed: false });
+            self.confirmations.insert((id, signer), &());
+            self.env().emit_event(TransactionSubmitted { id, signer, action });
+            self.env().emit_event(TransactionConfirmed { id, signer });
+            Ok(id)
+        }
+
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Replaces the guardians. `threshold` of them have to support a recovery, and the
+        /// owner has `delay` ms (at least `MIN_RECOVERY_DELAY`) to cancel it. An empty list
+        /// with threshold `0` disables recovery.
+        /// Any recovery in progress is dropped.
+        #[ink(message)]
+        pub fn set_guardians(&mut self, guardians: Vec<AccountId>, threshold: u32, delay: Timestamp) -> Result<(), WalletError> {
+            self.ensure_owner()?;
+            if guardians.len() > MAX_GUARDIANS as usize {
+                return Err(WalletError::TooManyGuardians);
+            }
+            for (index, guardian) in guardians.iter().enumerate() {
+                if guardians[..index].contains(guardian) {
+                    return Err(WalletError::AlreadyGuardian);
+                }
+            }
+            if threshold as usize > guardians.len() || (threshold == 0 && !guardians.is_empty()) {
+                return Err(WalletError::InvalidThreshold);
+            }
+            if !guardians.is_empty() && delay < MIN_RECOVERY_DELAY {
+                return Err(WalletError::InvalidRecoveryDelay);
+            }
+            if let Some(recovery) = self.recovery.take() {
+                self.env().emit_event(RecoveryCancelled { id: recovery.id });
+            }
+            self.guardians = guardians.clone();
+            self.recovery_threshold = threshold;
+            self.recovery_delay = delay;
+            self.env().emit_event(GuardiansChanged { guardians, threshold, delay });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn guardians(&self) -> (Vec<AccountId>, u32, Timestamp) {
+            (self.guardians.clone(), self.recovery_threshold, self.recovery_delay)
+        }
+
+        /// Starts moving the wallet to `new_owner`, counting the caller's support.
+        #[ink(message)]
+        pub fn initiate_recovery(&mut self, new_owner: AccountId) -> Result<RecoveryId, WalletError> {
+            let guardian = self.ensure_guardian()?;
+            if self.recovery.is_some() {
+                return Err(WalletError::RecoveryInProgress);
+            }
+            let id = self.next_recovery_id;
+            self.next_recovery_id = id.checked_add(1).ok_or(WalletError::Overflow)?;
+            let unlock_at = self.env().block_timestamp().saturating_add(self.recovery_delay);
+            self.recovery = Some(Recovery { id, new_owner, supporters: 1, unlock_at });
+            self.recovery_supporters.insert((id, guardian), &());
+            self.env().emit_event(RecoveryInitiated { id, new_owner, guardian, unlock_at });
+            Ok(id)
+        }
+
+        #[ink(message)]
+        pub fn support_recovery(&mut self, id: RecoveryId) -> Result<(), WalletError> {
+            let guardian = self.ensure_guardian()?;
+            let mut recovery = self.ensure_recovery(id)?;
+            if self.recovery_supporters.contains((id, guardian)) {
+                return Err(WalletError::AlreadySupported);
+            }
+            recovery.supporters = recovery.supporters.saturating_add(1);
+            self.recovery_supporters.insert((id, guardian), &());
+            self.recovery = Some(recovery);
+            self.env().emit_event(RecoverySupported { id, guardian, supporters: recovery.supporters });
+            Ok(())
+        }
+
+        /// Hands the wallet to the new owner once the recovery has enough support
+        /// and its delay passed. Anyone can call it.
+        #[ink(message)]
+        pub fn complete_recovery(&mut self, id: RecoveryId) -> Result<(), WalletError> {
+            let recovery = self.ensure_recovery(id)?;
+            if recovery.supporters < self.recovery_threshold {
+                return Err(WalletError::NotEnoughSupport);
+            }
+            if self.env().block_timestamp() < recovery.unlock_at {
+                return Err(WalletError::RecoveryLocked);
+            }
+            let previous_owner = self.owner;
+            self.owner = recovery.new_owner;
+            self.recovery = None;
+            self.env().emit_event(OwnerRecovered { id, previous_owner, new_owner: recovery.new_owner });
+            Ok(())
         }
 
+        /// Lets the current owner, who still has its key, stop a recovery.
         #[ink(message)]
-        pub fn send_coin(&mut self, to: AccountId, amount: Balance) -> Result<(), String> {
+        pub fn cancel_recovery(&mut self) -> Result<(), WalletError> {
+            self.ensure_owner()?;
+            let recovery = self.recovery.take().ok_or(WalletError::NoRecovery)?;
+            self.env().emit_event(RecoveryCancelled { id: recovery.id });
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn recovery(&self) -> Option<Recovery> {
+            self.recovery
+        }
+
+        fn ensure_guardian(&self) -> Result<AccountId, WalletError> {
             let caller = self.env().caller();
+            if !self.guardians.contains(&caller) {
+                return Err(WalletError::NotGuardian);
+            }
+            Ok(caller)
+        }
 
+        fn ensure_recovery(&self, id: RecoveryId) -> Result<Recovery, WalletError> {
+            self.recovery
+                .filter(|recovery| recovery.id == id)
+                .ok_or(WalletError::NoRecovery)
+        }
+
+        fn ensure_owner(&self) -> Result<(), WalletError> {
+            if self.env().caller() != self.owner {
+                return Err(WalletError::NotOwner);
+            }
+            Ok(())
+        }
+
+        fn ensure_owner_spending(&self) -> Result<(), WalletError> {
             // Verificar si el llamador es el propietario
-            if caller != self.owner {
-                return Err("Caller is not owner".into());
+            self.ensure_owner()?;
+
+            // En modo multisig los fondos solo salen con `execute`
+            if self.threshold > 0 {
+                return Err(WalletError::MultisigEnabled);
+            }
+            Ok(())
+        }
+
+        fn ensure_approver(&self) -> Result<(), WalletError> {
+            if self.limits.approver != Some(self.env().caller()) {
+                return Err(WalletError::NotApprover);
             }
+            Ok(())
+        }
 
-            // Verificar si el contrato tiene suficiente saldo
-            if self.balance < amount {
-                return Err("Balance insuficiente".into());
+        fn ensure_owner_or_approver(&self) -> Result<(), WalletError> {
+            if self.env().caller() == self.owner {
+                return Ok(());
             }
+            self.ensure_approver()
+        }
 
-            // Intentar la transferencia
-            self.env()
-                .transfer(to, amount)
-                .map_err(|_e| "Transfer failed".to_string())?;
+        /// Whether `limits` is looser than the current limits in any respect. Without
+        /// current limits there is nothing to loosen. Any approver change counts, since
+        /// an approver can release payments at once.
+        fn loosens_limits(&self, limits: &SpendingLimits) -> bool {
+            if self.limits.per_transaction.is_none() && self.limits.daily.is_none() {
+                return false;
+            }
+            let looser = |new: Option<Balance>, current: Option<Balance>| match (new, current) {
+                (_, None) => false,
+                (None, Some(_)) => true,
+                (Some(new), Some(current)) => new > current,
+            };
+            looser(limits.per_transaction, self.limits.per_transaction)
+                || looser(limits.daily, self.limits.daily)
+                || limits.delay < self.limits.delay
+                || limits.approver != self.limits.approver
+        }
+
+        fn within_limits(&self, amount: Balance) -> bool {
+            if self.limits.per_transaction.is_some_and(|limit| amount > limit) {
+                return false;
+            }
+            self.remaining_allowance_today().is_none_or(|remaining| amount <= remaining)
+        }
+
+        fn spent_today(&self) -> Balance {
+            let now = self.env().block_timestamp();
+            self.recent_spends
+                .iter()
+                .filter(|(at, _)| at.saturating_add(DAY) > now)
+                .fold(0, |total: Balance, (_, amount)| total.saturating_add(*amount))
+        }
 
-            // Actualizar el balance del contrato
-            self.balance = self.balance.checked_sub(amount).ok_or("Subdesbordamiento en el balance").unwrap();
+        /// Adds the payment to the rolling window, dropping the ones older than a day.
+        /// Without a daily limit nothing is tracked.
+        fn record_spend(&mut self, amount: Balance) {
+            if self.limits.daily.is_none() {
+                self.recent_spends.clear();
+                return;
+            }
+            if amount == 0 {
+                return;
+            }
+            let now = self.env().block_timestamp();
+            self.recent_spends.retain(|(at, _)| at.saturating_add(DAY) > now);
+            self.recent_spends.push((now, amount));
+        }
 
+        fn end_subscription(&mut self, id: SubscriptionId) -> Result<(), WalletError> {
+            if !self.subscriptions.contains(id) {
+                return Err(WalletError::UnknownSubscription);
+            }
+            self.subscriptions.remove(id);
+            self.env().emit_event(SubscriptionCancelled { id });
             Ok(())
         }
 
+        fn remove_schedule(&mut self, id: ScheduleId) -> Result<ScheduledPayment, WalletError> {
+            let payment = self.schedules.get(id).ok_or(WalletError::UnknownSchedule)?;
+            self.schedules.remove(id);
+            self.pending_schedules.retain(|pending| *pending != id);
+            Ok(payment)
+        }
+
+        fn ensure_signer(&self) -> Result<AccountId, WalletError> {
+            let caller = self.env().caller();
+            if !self.signers.contains(&caller) {
+                return Err(WalletError::NotSigner);
+            }
+            Ok(caller)
+        }
 
+        fn ensure_pending(&self, id: TransactionId) -> Result<Transaction, WalletError> {
+            let transaction = self.transactions.get(id).ok_or(WalletError::UnknownTransaction)?;
+            if transaction.executed {
+                return Err(WalletError::AlreadyExecuted);
+            }
+            Ok(transaction)
+        }
 
-        #[ink(message)]
-        pub fn get_balance(&self) -> Balance {
-            self.balance
+        fn transfer_out(&mut self, to: AccountId, amount: Balance, data: &[u8]) -> Result<(), WalletError> {
+            if self.available_balance() < amount {
+                return Err(WalletError::InsufficientBalance);
+            }
+            if data.is_empty() {
+                self.env()
+                    .transfer(to, amount)
+                    .map_err(|_| WalletError::TransferFailed)?;
+            } else {
+                let (selector, arguments) = data.split_at(4);
+                let selector: [u8; 4] = selector.try_into().map_err(|_| WalletError::InvalidCallData)?;
+                build_call::<ink::env::DefaultEnvironment>()
+                    .call(to)
+                    .transferred_value(amount)
+                    .exec_input(ExecutionInput::new(Selector::new(selector)).push_arg(CallInput(arguments)))
+                    .returns::<()>()
+                    .try_invoke()
+                    .map_err(|_| WalletError::CallFailed)?
+                    .map_err(|_| WalletError::CallFailed)?;
+            }
+            self.balance = self.balance.saturating_sub(amount);
+            Ok(())
+        }
+
+        fn add_signer(&mut self, account: AccountId) -> Result<(), WalletError> {
+            if self.signers.contains(&account) {
+                return Err(WalletError::AlreadySigner);
+            }
+            if self.signers.len() >= MAX_SIGNERS as usize {
+                return Err(WalletError::TooManySigners);
+            }
+            self.signers.push(account);
+            self.env().emit_event(SignerAdded { account });
+            Ok(())
+        }
+
+        fn remove_signer(&mut self, account: AccountId) -> Result<(), WalletError> {
+            let index = self
+                .signers
+                .iter()
+                .position(|signer| *signer == account)
+                .ok_or(WalletError::NotSigner)?;
+            // No se puede quedar con menos firmantes que el umbral
+            if self.signers.len() - 1 < self.threshold as usize {
+                return Err(WalletError::InvalidThreshold);
+            }
+            self.signers.swap_remove(index);
+            self.env().emit_event(SignerRemoved { account });
+            Ok(())
+        }
+
+        fn change_threshold(&mut self, threshold: u32) -> Result<(), WalletError> {
+            if threshold == 0 || threshold as usize > self.signers.len() {
+                return Err(WalletError::InvalidThreshold);
+            }
+            self.threshold = threshold;
+            self.env().emit_event(ThresholdChanged { threshold });
+            Ok(())
         }
 
     }
@@ -83,38 +1335,49 @@ mod personal_wallet {
         use super::*;
         use ink::env::test;
 
+        /// Cuenta del contrato en los tests, distinta de `alice` para que sus fondos no se mezclen.
+        fn contract_account() -> AccountId {
+            AccountId::from([0xC0; 32])
+        }
+
+        /// Crea la billetera con `alice` como owner y el contrato sin fondos.
+        fn new_wallet() -> PersonalWallet {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_callee::<ink::env::DefaultEnvironment>(contract_account());
+            test::set_account_balance::<ink::env::DefaultEnvironment>(contract_account(), 0);
+            PersonalWallet::new()
+        }
+
         /// We test a simple use case of our contract.
         #[ink::test]
         fn it_works() {
-            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
-            // Configurar el balance inicial del contrato en 0
-            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.alice, 0);
-            let simple_counter = PersonalWallet::new();
+            // Crear la billetera con el balance inicial del contrato en 0
+            let simple_counter = new_wallet();
             assert_eq!(simple_counter.get_balance(), 0);
         }
 
         #[ink::test]
         fn test_deposit_from_owner() {
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
-            // Vaciar el balance de Alice (no es necesario pero lo puedes hacer para estar seguro)
-            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.alice, 0);
-            let mut wallet = PersonalWallet::new();
+            let mut wallet = new_wallet();
             assert_eq!(wallet.get_balance(), 0);
 
             // Cargar fondos a Alice
             ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.alice, 9000000);
 
-            // Simular transferencia de 2000000 unidades al contrato
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(2000000);
-
             // Establecer a Alice como el "caller" para llamar a la función `deposit`
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
 
+            // Simular transferencia de 2000000 unidades al contrato
+            ink::env::test::transfer_in::<ink::env::DefaultEnvironment>(2000000);
+
             // Llamar a la función `deposit` para recibir los fondos
-            wallet.deposit();
+            assert_eq!(wallet.deposit(), Ok(()));
             
             // Verificar el balance del contrato
             assert_eq!(wallet.get_balance(), 2000000);
+            assert_eq!(wallet.tracked_balance(), 2000000);
         }
 
 
@@ -122,14 +1385,12 @@ mod personal_wallet {
         #[ink::test]
         fn test_deposit_from_non_owner() {
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
-            // Configurar el balance inicial del contrato en 0
-            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.alice, 0);
-            let mut wallet = PersonalWallet::new();
+            // Crear la billetera con el balance inicial del contrato en 0
+            let mut wallet = new_wallet();
             
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(50);
-            wallet.deposit();
+            ink::env::test::transfer_in::<ink::env::DefaultEnvironment>(50);
+            assert_eq!(wallet.deposit(), Ok(()));
             
             assert_eq!(wallet.get_balance(), 50);
         }
@@ -137,34 +1398,31 @@ mod personal_wallet {
         #[ink::test]
         fn test_send_coin_from_owner() {
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
-            // Configurar el balance inicial del contrato en 0
-            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.alice, 0);
-            let mut wallet = PersonalWallet::new();
+            // Crear la billetera con el balance inicial del contrato en 0
+            let mut wallet = new_wallet();
 
             ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.alice, 1000000);
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
-            wallet.deposit();
+            ink::env::test::transfer_in::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(wallet.deposit(), Ok(()));
             
             let result = wallet.send_coin(accounts.bob, 50);
             assert!(result.is_ok());
             assert_eq!(wallet.get_balance(), 50);
+            assert_eq!(wallet.tracked_balance(), 50);
         }
 
         #[ink::test]
         fn test_send_coin_from_non_owner() {
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
-            ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
-            // Configurar el balance inicial del contrato en 0
-            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.alice, 0);
-            let mut wallet = PersonalWallet::new();
+            // Crear la billetera con el balance inicial del contrato en 0
+            let mut wallet = new_wallet();
             
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(100);
-            wallet.deposit();
+            ink::env::test::transfer_in::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(wallet.deposit(), Ok(()));
             
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
             let result = wallet.send_coin(accounts.charlie, 50);
-            assert!(result.is_err());
+            assert_eq!(result, Err(WalletError::NotOwner));
             assert_eq!(wallet.get_balance(), 100);
         }
 
@@ -180,13 +1438,11 @@ mod personal_wallet {
 
 
             let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
-            // Configurar el balance inicial del contrato en 0
-            ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.alice, 0);
-            // Crear el contrato con saldo suficiente para realizar la transferencia
-            let mut wallet = PersonalWallet::new();
+            // Crear el contrato con el balance inicial en 0
+            let mut wallet = new_wallet();
             ink::env::test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.alice, 1000000);
-            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1000);
-            wallet.deposit(); // Depositar 1000 en el contrato
+            ink::env::test::transfer_in::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wallet.deposit(), Ok(())); // Depositar 1000 en el contrato
 
             // Intentar realizar una transferencia de 500, que debe ser exitosa
             let result = wallet.send_coin(accounts.bob, 500);
@@ -198,7 +1454,653 @@ mod personal_wallet {
             let result_fail = wallet.send_coin(accounts.bob, 2000);
 
             // Verificamos que el error proviene de la transferencia fallida
-            assert_eq!(result_fail, Err("Balance insuficiente".to_string()));
+            assert_eq!(result_fail, Err(WalletError::InsufficientBalance));
+        }
+
+        #[ink::test]
+        fn spending_limits_are_enforced() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+            test::transfer_in::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wallet.deposit(), Ok(()));
+            assert_eq!(wallet.remaining_allowance_today(), None);
+
+            // Sin límite diario no se lleva registro de los envíos, y enviar 0 no se permite
+            assert_eq!(wallet.send_coin(accounts.bob, 0), Err(WalletError::ZeroAmount));
+            assert_eq!(wallet.send_coin(accounts.bob, 10), Ok(()));
+            assert!(wallet.recent_spends.is_empty());
+
+            // Solo el owner configura los límites: 100 por envío y 250 por día
+            let limits = SpendingLimits {
+                per_transaction: Some(100),
+                daily: Some(250),
+                delay: DAY,
+                approver: Some(accounts.charlie),
+            };
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.set_spending_limits(limits), Err(WalletError::NotOwner));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.set_spending_limits(limits), Ok(()));
+
+            // Un envío por encima del límite por transacción falla
+            assert_eq!(wallet.send_coin(accounts.bob, 101), Err(WalletError::LimitExceeded));
+            assert_eq!(wallet.send_coin(accounts.bob, 100), Ok(()));
+            assert_eq!(wallet.send_coin(accounts.bob, 100), Ok(()));
+            assert_eq!(wallet.remaining_allowance_today(), Some(50));
+            assert_eq!(wallet.send_coin(accounts.bob, 51), Err(WalletError::LimitExceeded));
+
+            // La ventana es móvil: el cupo vuelve 24 horas después de cada envío
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(DAY - 1);
+            assert_eq!(wallet.remaining_allowance_today(), Some(50));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(DAY);
+            assert_eq!(wallet.remaining_allowance_today(), Some(250));
+        }
+
+        #[ink::test]
+        fn large_payments_need_delay_or_approval() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+            test::transfer_in::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wallet.deposit(), Ok(()));
+            let limits = SpendingLimits {
+                per_transaction: Some(100),
+                daily: None,
+                delay: DAY,
+                approver: Some(accounts.charlie),
+            };
+            assert_eq!(wallet.set_spending_limits(limits), Ok(()));
+
+            // Un pago grande queda bloqueado hasta que pase la demora
+            assert_eq!(wallet.request_payment(accounts.bob, 400), Ok(0));
+            assert_eq!(wallet.execute_payment(0), Err(WalletError::PaymentLocked));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(DAY);
+            assert_eq!(wallet.execute_payment(0), Ok(()));
+            assert_eq!(wallet.get_balance(), 600);
+            assert_eq!(wallet.execute_payment(0), Err(WalletError::UnknownPayment));
+
+            // O antes, si el aprobador secundario lo aprueba
+            assert_eq!(wallet.request_payment(accounts.bob, 300), Ok(1));
+            assert_eq!(wallet.approve_payment(1), Err(WalletError::NotApprover));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(wallet.approve_payment(1), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.execute_payment(1), Ok(()));
+            assert_eq!(wallet.get_balance(), 300);
+
+            // El owner puede cancelar un pago pendiente
+            assert_eq!(wallet.request_payment(accounts.bob, 300), Ok(2));
+            assert_eq!(wallet.cancel_payment(2), Ok(()));
+            assert_eq!(wallet.pending_payment(2), None);
+            assert_eq!(wallet.cancel_payment(2), Err(WalletError::UnknownPayment));
+
+            // Y el aprobador puede vetarlo; nadie más
+            assert_eq!(wallet.request_payment(accounts.bob, 300), Ok(3));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.cancel_payment(3), Err(WalletError::NotApprover));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(wallet.cancel_payment(3), Ok(()));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(3 * DAY);
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.execute_payment(3), Err(WalletError::UnknownPayment));
+        }
+
+        #[ink::test]
+        fn owner_cannot_lift_limits_at_once() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+            test::transfer_in::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wallet.deposit(), Ok(()));
+
+            // Con límites, la demora no puede ser menor al mínimo
+            let mut limits = SpendingLimits {
+                per_transaction: Some(100),
+                daily: Some(250),
+                delay: 0,
+                approver: Some(accounts.charlie),
+            };
+            assert_eq!(wallet.set_spending_limits(limits), Err(WalletError::InvalidLimits));
+            limits.delay = DAY;
+            assert_eq!(wallet.set_spending_limits(limits), Ok(()));
+
+            // Con la clave del owner se intenta quitar los límites: el cambio queda pendiente
+            assert_eq!(wallet.set_spending_limits(SpendingLimits::default()), Ok(()));
+            assert_eq!(wallet.spending_limits(), limits);
+            assert_eq!(
+                wallet.pending_spending_limits(),
+                Some(PendingLimits { limits: SpendingLimits::default(), unlock_at: DAY })
+            );
+            assert_eq!(wallet.send_coin(accounts.bob, 101), Err(WalletError::LimitExceeded));
+            assert_eq!(wallet.apply_spending_limits(), Err(WalletError::LimitsLocked));
+
+            // Cambiar el aprobador o acortar la demora también espera
+            let other_approver = SpendingLimits { approver: Some(accounts.bob), ..limits };
+            assert_eq!(wallet.set_spending_limits(other_approver), Ok(()));
+            assert_eq!(wallet.spending_limits(), limits);
+            let shorter_delay = SpendingLimits { delay: MIN_LIMITS_DELAY, ..limits };
+            assert_eq!(wallet.set_spending_limits(shorter_delay), Ok(()));
+            assert_eq!(wallet.spending_limits(), limits);
+
+            // El aprobador rechaza el cambio
+            assert_eq!(wallet.set_spending_limits(SpendingLimits::default()), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.cancel_spending_limits(), Err(WalletError::NotApprover));
+            assert_eq!(wallet.approve_spending_limits(), Err(WalletError::NotApprover));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(wallet.cancel_spending_limits(), Ok(()));
+            assert_eq!(wallet.pending_spending_limits(), None);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(DAY);
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.apply_spending_limits(), Err(WalletError::NoPendingLimits));
+
+            // Endurecer los límites se aplica enseguida
+            let tighter = SpendingLimits { per_transaction: Some(50), ..limits };
+            assert_eq!(wallet.set_spending_limits(tighter), Ok(()));
+            assert_eq!(wallet.spending_limits(), tighter);
+            assert_eq!(wallet.pending_spending_limits(), None);
+
+            // Un aumento se aplica pasada la demora, o antes si el aprobador lo aprueba
+            assert_eq!(wallet.set_spending_limits(limits), Ok(()));
+            assert_eq!(wallet.apply_spending_limits(), Err(WalletError::LimitsLocked));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(2 * DAY);
+            assert_eq!(wallet.apply_spending_limits(), Ok(()));
+            assert_eq!(wallet.spending_limits(), limits);
+            assert_eq!(wallet.set_spending_limits(SpendingLimits::default()), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(wallet.approve_spending_limits(), Ok(()));
+            assert_eq!(wallet.spending_limits(), SpendingLimits::default());
+        }
+
+        #[ink::test]
+        fn adding_an_approver_waits_for_the_delay() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+            test::transfer_in::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wallet.deposit(), Ok(()));
+
+            // Límites sin aprobador: los pagos grandes esperan la demora
+            let limits = SpendingLimits {
+                per_transaction: Some(100),
+                daily: Some(250),
+                delay: DAY,
+                approver: None,
+            };
+            assert_eq!(wallet.set_spending_limits(limits), Ok(()));
+
+            // Con la clave del owner se intenta sumar un aprobador propio: queda pendiente
+            let with_approver = SpendingLimits { approver: Some(accounts.eve), ..limits };
+            assert_eq!(wallet.set_spending_limits(with_approver), Ok(()));
+            assert_eq!(wallet.spending_limits(), limits);
+
+            // Ese aprobador todavía no puede liberar el cambio ni un pago grande
+            assert_eq!(wallet.request_payment(accounts.eve, 500), Ok(0));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(wallet.approve_spending_limits(), Err(WalletError::NotApprover));
+            assert_eq!(wallet.approve_payment(0), Err(WalletError::NotApprover));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.execute_payment(0), Err(WalletError::PaymentLocked));
+            assert_eq!(wallet.apply_spending_limits(), Err(WalletError::LimitsLocked));
+
+            // Pasada la demora el aprobador queda aplicado
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(DAY);
+            assert_eq!(wallet.apply_spending_limits(), Ok(()));
+            assert_eq!(wallet.spending_limits(), with_approver);
+        }
+
+        #[ink::test]
+        fn scheduled_payments_reserve_funds() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+            test::transfer_in::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wallet.deposit(), Ok(()));
+            test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.eve, 0);
+
+            // Solo el owner programa pagos, y no por más de lo disponible
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.schedule_payment(accounts.eve, 600, 5_000), Err(WalletError::NotOwner));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.schedule_payment(accounts.eve, 1001, 5_000), Err(WalletError::InsufficientBalance));
+            assert_eq!(wallet.schedule_payment(accounts.eve, 600, 5_000), Ok(0));
+
+            // Los fondos reservados no se pueden gastar de otra forma
+            assert_eq!(wallet.reserved_balance(), 600);
+            assert_eq!(wallet.available_balance(), 400);
+            assert_eq!(wallet.send_coin(accounts.bob, 401), Err(WalletError::InsufficientBalance));
+            assert_eq!(wallet.schedule_payment(accounts.eve, 401, 5_000), Err(WalletError::InsufficientBalance));
+
+            // Cualquiera lo ejecuta, pero recién cuando llega la fecha
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(wallet.execute_due(0), Err(WalletError::NotDue));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(5_000);
+            assert_eq!(wallet.execute_due(0), Ok(()));
+            assert_eq!(wallet.execute_due(0), Err(WalletError::UnknownSchedule));
+            assert_eq!(test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.eve), Ok(600));
+            assert_eq!(wallet.reserved_balance(), 0);
+            assert_eq!(wallet.get_balance(), 400);
+        }
+
+        #[ink::test]
+        fn scheduled_payments_respect_limits() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+            test::transfer_in::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wallet.deposit(), Ok(()));
+            let limits = SpendingLimits {
+                per_transaction: Some(200),
+                daily: Some(250),
+                delay: DAY,
+                approver: None,
+            };
+            assert_eq!(wallet.set_spending_limits(limits), Ok(()));
+
+            // La fecha tiene que ser futura y el monto entrar en los límites
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(wallet.schedule_payment(accounts.eve, 100, 1_000), Err(WalletError::InvalidSchedule));
+            assert_eq!(wallet.schedule_payment(accounts.eve, 201, 5_000), Err(WalletError::LimitExceeded));
+            assert_eq!(wallet.schedule_payment(accounts.eve, 200, 5_000), Ok(0));
+
+            // Al ejecutarse cuenta para el cupo diario: si ya se gastó, hay que esperar
+            assert_eq!(wallet.send_coin(accounts.bob, 100), Ok(()));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(5_000);
+            assert_eq!(wallet.execute_due(0), Err(WalletError::LimitExceeded));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000 + DAY);
+            assert_eq!(wallet.execute_due(0), Ok(()));
+            assert_eq!(wallet.remaining_allowance_today(), Some(50));
+
+            // En modo multisig los pagos programados no se ejecutan; el owner libera la reserva
+            assert_eq!(wallet.schedule_payment(accounts.eve, 50, 3 * DAY), Ok(1));
+            assert_eq!(wallet.enable_multisig(vec![accounts.bob, accounts.charlie], 2), Ok(()));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(3 * DAY);
+            assert_eq!(wallet.execute_due(1), Err(WalletError::MultisigEnabled));
+            assert_eq!(wallet.cancel_scheduled(1), Ok(()));
+            assert_eq!(wallet.reserved_balance(), 0);
+        }
+
+        #[ink::test]
+        fn scheduled_payments_are_listed_and_cancelled() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+            test::transfer_in::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wallet.deposit(), Ok(()));
+
+            // Programamos tres pagos
+            assert_eq!(wallet.schedule_payment(accounts.bob, 100, 1_000), Ok(0));
+            assert_eq!(wallet.schedule_payment(accounts.charlie, 200, 2_000), Ok(1));
+            assert_eq!(wallet.schedule_payment(accounts.django, 300, 3_000), Ok(2));
+            assert_eq!(wallet.pending_schedules_len(), 3);
+
+            // Cancelar el del medio libera su reserva y lo saca del listado
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.cancel_scheduled(1), Err(WalletError::NotOwner));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.cancel_scheduled(1), Ok(()));
+            assert_eq!(wallet.cancel_scheduled(1), Err(WalletError::UnknownSchedule));
+            assert_eq!(wallet.reserved_balance(), 400);
+
+            // El listado está paginado y conserva el orden
+            let page = wallet.pending_schedules(0, 1);
+            assert_eq!(page.len(), 1);
+            assert_eq!(page[0].0, 0);
+            let page = wallet.pending_schedules(1, 10);
+            assert_eq!(page.len(), 1);
+            assert_eq!(page[0], (2, ScheduledPayment { to: accounts.django, amount: 300, execute_after: 3_000 }));
+            assert!(wallet.pending_schedules(2, 10).is_empty());
+        }
+
+        #[ink::test]
+        fn subscriptions_are_collected_by_payee_each_period() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+            test::transfer_in::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wallet.deposit(), Ok(()));
+            test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.eve, 0);
+
+            // Solo el owner crea suscripciones, con período y cantidad de períodos válidos
+            assert_eq!(wallet.create_subscription(accounts.eve, 100, 0, 2), Err(WalletError::InvalidSubscription));
+            assert_eq!(
+                wallet.create_subscription(accounts.eve, 100, MIN_SUBSCRIPTION_PERIOD - 1, 2),
+                Err(WalletError::InvalidSubscription)
+            );
+            assert_eq!(wallet.create_subscription(accounts.eve, 100, DAY, 0), Err(WalletError::InvalidSubscription));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.create_subscription(accounts.eve, 100, DAY, 2), Err(WalletError::NotOwner));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.create_subscription(accounts.eve, 100, DAY, 2), Ok(0));
+
+            // Solo `eve` cobra, y recién cuando pasa un período
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(wallet.collect(0), Err(WalletError::NotDue));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(DAY);
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.collect(0), Err(WalletError::NotPayee));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(wallet.collect(0), Ok(()));
+            assert_eq!(wallet.collect(0), Err(WalletError::NotDue));
+
+            // Al cobrar el último período la suscripción termina
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(3 * DAY);
+            assert_eq!(wallet.collect(0), Ok(()));
+            assert_eq!(wallet.subscription(0), None);
+            assert_eq!(wallet.collect(0), Err(WalletError::UnknownSubscription));
+            assert_eq!(test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.eve), Ok(200));
+            assert_eq!(wallet.get_balance(), 800);
+        }
+
+        #[ink::test]
+        fn subscriptions_can_be_cancelled_by_owner() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+            test::transfer_in::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wallet.deposit(), Ok(()));
+            assert_eq!(wallet.create_subscription(accounts.eve, 100, DAY, 12), Ok(0));
+
+            // Nadie más que el owner puede cancelarla
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(wallet.cancel_subscription(0), Err(WalletError::NotOwner));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.cancel_subscription(0), Ok(()));
+            assert_eq!(wallet.cancel_subscription(0), Err(WalletError::UnknownSubscription));
+
+            // Una suscripción cancelada ya no se puede cobrar
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(DAY);
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(wallet.collect(0), Err(WalletError::UnknownSubscription));
+        }
+
+        #[ink::test]
+        fn multisig_transfers_respect_limits() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+            test::transfer_in::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wallet.deposit(), Ok(()));
+            let limits = SpendingLimits {
+                per_transaction: Some(100),
+                daily: Some(150),
+                delay: DAY,
+                approver: Some(accounts.charlie),
+            };
+            assert_eq!(wallet.set_spending_limits(limits), Ok(()));
+
+            // Con la clave del owner se arma una multisig 1 de 1 con un signer propio
+            assert_eq!(wallet.enable_multisig(vec![accounts.eve], 1), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+
+            // Ese signer no puede vaciar la wallet: rigen los mismos límites que en `send_coin`
+            assert_eq!(wallet.submit_transaction(accounts.eve, 1000, Vec::new()), Ok(0));
+            assert_eq!(wallet.execute(0), Err(WalletError::LimitExceeded));
+            assert_eq!(wallet.submit_transaction(accounts.eve, 100, Vec::new()), Ok(1));
+            assert_eq!(wallet.execute(1), Ok(()));
+            assert_eq!(wallet.submit_transaction(accounts.eve, 100, Vec::new()), Ok(2));
+            assert_eq!(wallet.execute(2), Err(WalletError::LimitExceeded));
+            assert_eq!(wallet.tracked_balance(), 900);
+            assert_eq!(wallet.remaining_allowance_today(), Some(50));
+        }
+
+        #[ink::test]
+        fn subscriptions_respect_limits_and_multisig() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+            test::transfer_in::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wallet.deposit(), Ok(()));
+            let limits = SpendingLimits {
+                per_transaction: Some(100),
+                daily: Some(250),
+                delay: DAY,
+                approver: None,
+            };
+            assert_eq!(wallet.set_spending_limits(limits), Ok(()));
+            assert_eq!(wallet.create_subscription(accounts.eve, 101, DAY, 5), Err(WalletError::LimitExceeded));
+            assert_eq!(wallet.create_subscription(accounts.eve, 100, DAY, 5), Ok(0));
+
+            // Tras tres períodos sin cobrar, el cupo diario solo deja cobrar dos
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(3 * DAY);
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(wallet.collect(0), Ok(()));
+            assert_eq!(wallet.collect(0), Ok(()));
+            assert_eq!(wallet.collect(0), Err(WalletError::LimitExceeded));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(4 * DAY);
+            assert_eq!(wallet.collect(0), Ok(()));
+
+            // En modo multisig ya no se cobra, y los firmantes pueden cancelarla por quórum
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.enable_multisig(vec![accounts.bob, accounts.charlie], 2), Ok(()));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(5 * DAY);
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(wallet.collect(0), Err(WalletError::MultisigEnabled));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.submit_cancel_subscription(0), Ok(0));
+            assert_eq!(wallet.execute(0), Err(WalletError::NotEnoughConfirmations));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(wallet.confirm(0), Ok(()));
+            assert_eq!(wallet.execute(0), Ok(()));
+            assert_eq!(wallet.subscription(0), None);
+        }
+
+        /// Crea la billetera con `bob`, `charlie` y `django` como guardianes, 2 de 3 y una demora de un día.
+        fn new_guarded_wallet() -> PersonalWallet {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+            let guardians = vec![accounts.bob, accounts.charlie, accounts.django];
+            assert_eq!(wallet.set_guardians(guardians, 2, DAY), Ok(()));
+            wallet
+        }
+
+        #[ink::test]
+        fn set_guardians_validates_threshold() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.set_guardians(vec![accounts.bob], 1, DAY), Err(WalletError::NotOwner));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.set_guardians(vec![accounts.bob, accounts.bob], 1, DAY), Err(WalletError::AlreadyGuardian));
+            assert_eq!(wallet.set_guardians(vec![accounts.bob], 2, DAY), Err(WalletError::InvalidThreshold));
+            assert_eq!(wallet.set_guardians(vec![accounts.bob], 0, DAY), Err(WalletError::InvalidThreshold));
+
+            // Con guardianes la demora no puede ser menor al mínimo
+            assert_eq!(wallet.set_guardians(vec![accounts.bob], 1, 0), Err(WalletError::InvalidRecoveryDelay));
+            assert_eq!(
+                wallet.set_guardians(vec![accounts.bob], 1, MIN_RECOVERY_DELAY - 1),
+                Err(WalletError::InvalidRecoveryDelay)
+            );
+            assert_eq!(wallet.set_guardians(Vec::new(), 0, 0), Ok(()));
+
+            // Sin guardianes nadie puede iniciar una recuperación
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.initiate_recovery(accounts.eve), Err(WalletError::NotGuardian));
+        }
+
+        #[ink::test]
+        fn guardians_recover_the_wallet_after_delay() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_guarded_wallet();
+
+            // `bob` inicia la recuperación hacia `eve`; no puede haber dos a la vez
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(wallet.initiate_recovery(accounts.eve), Err(WalletError::NotGuardian));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.initiate_recovery(accounts.eve), Ok(0));
+            assert_eq!(wallet.initiate_recovery(accounts.bob), Err(WalletError::RecoveryInProgress));
+            assert_eq!(wallet.support_recovery(0), Err(WalletError::AlreadySupported));
+            assert_eq!(wallet.complete_recovery(0), Err(WalletError::NotEnoughSupport));
+
+            // `charlie` la apoya, pero hay que esperar la demora
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(wallet.support_recovery(1), Err(WalletError::NoRecovery));
+            assert_eq!(wallet.support_recovery(0), Ok(()));
+            assert_eq!(wallet.complete_recovery(0), Err(WalletError::RecoveryLocked));
+
+            // Pasada la demora cualquiera la completa y `eve` pasa a ser owner
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(DAY);
+            assert_eq!(wallet.complete_recovery(0), Ok(()));
+            assert_eq!(wallet.owner(), accounts.eve);
+            assert_eq!(wallet.recovery(), None);
+
+            // `eve` ya puede enviar fondos y `alice` no; un monto vacío sigue sin permitirse
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.send_coin(accounts.alice, 0), Err(WalletError::NotOwner));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(wallet.send_coin(accounts.eve, 0), Err(WalletError::ZeroAmount));
+        }
+
+        #[ink::test]
+        fn owner_can_cancel_recovery() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_guarded_wallet();
+
+            // Dos guardianes apoyan una recuperación
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.initiate_recovery(accounts.eve), Ok(0));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(wallet.support_recovery(0), Ok(()));
+            assert_eq!(wallet.cancel_recovery(), Err(WalletError::NotOwner));
+
+            // La cuenta owner actual todavía tiene su clave y la cancela durante la demora
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.cancel_recovery(), Ok(()));
+            assert_eq!(wallet.cancel_recovery(), Err(WalletError::NoRecovery));
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(DAY);
+            assert_eq!(wallet.complete_recovery(0), Err(WalletError::NoRecovery));
+            assert_eq!(wallet.owner(), accounts.alice);
+
+            // Una nueva recuperación empieza de cero
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(wallet.initiate_recovery(accounts.eve), Ok(1));
+            assert_eq!(wallet.recovery().map(|recovery| recovery.supporters), Some(1));
+        }
+
+        /// Crea la billetera con fondos y multisig 2 de 3 entre `bob`, `charlie` y `django`.
+        fn new_multisig_wallet() -> PersonalWallet {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+            test::transfer_in::<ink::env::DefaultEnvironment>(1000);
+            assert_eq!(wallet.deposit(), Ok(()));
+            let signers = vec![accounts.bob, accounts.charlie, accounts.django];
+            assert_eq!(wallet.enable_multisig(signers, 2), Ok(()));
+            wallet
+        }
+
+        #[ink::test]
+        fn enable_multisig_validates_signers() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+
+            // Solo el owner lo activa, sin firmantes repetidos y con un umbral válido
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.enable_multisig(vec![accounts.bob], 1), Err(WalletError::NotOwner));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.enable_multisig(vec![accounts.bob, accounts.bob], 1), Err(WalletError::AlreadySigner));
+            assert_eq!(wallet.enable_multisig(vec![accounts.bob], 2), Err(WalletError::InvalidThreshold));
+            assert_eq!(wallet.enable_multisig(vec![accounts.bob], 0), Err(WalletError::InvalidThreshold));
+            assert_eq!(wallet.submit_transaction(accounts.eve, 1, Vec::new()), Err(WalletError::MultisigDisabled));
+
+            assert_eq!(wallet.enable_multisig(vec![accounts.bob], 1), Ok(()));
+            assert_eq!(wallet.enable_multisig(vec![accounts.bob], 1), Err(WalletError::MultisigEnabled));
+
+            // A partir de ahora el owner no puede enviar fondos por su cuenta
+            assert_eq!(wallet.send_coin(accounts.eve, 1), Err(WalletError::MultisigEnabled));
+        }
+
+        #[ink::test]
+        fn multisig_transfer_needs_threshold() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_multisig_wallet();
+            test::set_account_balance::<ink::env::DefaultEnvironment>(accounts.eve, 0);
+
+            // Solo los firmantes proponen; `bob` propone y confirma automáticamente
+            assert_eq!(wallet.submit_transaction(accounts.eve, 300, Vec::new()), Err(WalletError::NotSigner));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.submit_transaction(accounts.eve, 300, vec![1, 2]), Err(WalletError::InvalidCallData));
+            assert_eq!(wallet.submit_transaction(accounts.eve, 300, Vec::new()), Ok(0));
+            assert_eq!(wallet.confirm(0), Err(WalletError::AlreadyConfirmed));
+            assert_eq!(wallet.execute(0), Err(WalletError::NotEnoughConfirmations));
+
+            // `charlie` confirma, se arrepiente y vuelve a confirmar
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(wallet.revoke_confirmation(0), Err(WalletError::NotConfirmed));
+            assert_eq!(wallet.confirm(0), Ok(()));
+            assert_eq!(wallet.revoke_confirmation(0), Ok(()));
+            assert_eq!(wallet.confirmation_count(0), 1);
+            assert_eq!(wallet.confirm(0), Ok(()));
+
+            // Con 2 de 3 confirmaciones se ejecuta una sola vez
+            assert_eq!(wallet.execute(0), Ok(()));
+            assert_eq!(wallet.execute(0), Err(WalletError::AlreadyExecuted));
+            assert_eq!(wallet.confirm(0), Err(WalletError::AlreadyExecuted));
+            assert_eq!(test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.eve), Ok(300));
+            assert_eq!(wallet.get_balance(), 700);
+            assert_eq!(wallet.execute(1), Err(WalletError::UnknownTransaction));
+        }
+
+        #[ink::test]
+        fn multisig_signer_changes_need_quorum() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_multisig_wallet();
+
+            // `bob` y `charlie` agregan a `eve` como firmante
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.submit_add_signer(accounts.eve), Ok(0));
+            assert_eq!(wallet.execute(0), Err(WalletError::NotEnoughConfirmations));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(wallet.confirm(0), Ok(()));
+            assert_eq!(wallet.execute(0), Ok(()));
+            assert_eq!(wallet.signers().len(), 4);
+
+            // Suben el umbral a 3
+            assert_eq!(wallet.submit_change_threshold(3), Ok(1));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(wallet.confirm(1), Ok(()));
+            assert_eq!(wallet.execute(1), Ok(()));
+            assert_eq!(wallet.threshold(), 3);
+
+            // Las confirmaciones de un firmante removido dejan de contar
+            assert_eq!(wallet.submit_remove_signer(accounts.django), Ok(2));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.django);
+            assert_eq!(wallet.submit_change_threshold(1), Ok(3));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.confirm(2), Ok(()));
+            assert_eq!(wallet.confirm(3), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert_eq!(wallet.confirm(2), Ok(()));
+            assert_eq!(wallet.execute(2), Ok(()));
+            assert_eq!(wallet.signers().len(), 3);
+            assert_eq!(wallet.confirmation_count(3), 1);
+            assert_eq!(wallet.execute(3), Err(WalletError::NotEnoughConfirmations));
+
+            // No se puede quedar con menos firmantes que el umbral
+            assert_eq!(wallet.submit_remove_signer(accounts.eve), Ok(4));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.confirm(4), Ok(()));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.eve);
+            assert_eq!(wallet.confirm(4), Ok(()));
+            assert_eq!(wallet.execute(4), Err(WalletError::InvalidThreshold));
+        }
+
+        #[ink::test]
+        fn untracked_funds_are_reconciled() {
+            let accounts = ink::env::test::default_accounts::<ink::env::DefaultEnvironment>();
+            let mut wallet = new_wallet();
+            test::transfer_in::<ink::env::DefaultEnvironment>(100);
+            assert_eq!(wallet.deposit(), Ok(()));
+
+            // Una transferencia directa, sin pasar por `deposit`, no queda registrada
+            test::set_account_balance::<ink::env::DefaultEnvironment>(contract_account(), 130);
+            assert_eq!(wallet.get_balance(), 130);
+            assert_eq!(wallet.tracked_balance(), 100);
+            assert_eq!(wallet.untracked_balance(), 30);
+
+            // Igual se puede enviar, porque el balance real es la fuente de verdad
+            assert_eq!(wallet.send_coin(accounts.bob, 120), Ok(()));
+            assert_eq!(wallet.get_balance(), 10);
+            assert_eq!(wallet.untracked_balance(), 10);
+
+            // Solo el owner puede igualar el balance registrado con el real
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            assert_eq!(wallet.sync_balance(), Err(WalletError::NotOwner));
+            assert_eq!(wallet.tracked_balance(), 0);
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert_eq!(wallet.sync_balance(), Ok(10));
+            assert_eq!(wallet.tracked_balance(), 10);
+            assert_eq!(wallet.untracked_balance(), 0);
         }
 
 